@@ -0,0 +1,106 @@
+use std::io::{Error, ErrorKind};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{mock, Handle, Mock};
+
+/// Create a blocking Mock I/O object and a controlling Handle
+pub fn blocking_mock() -> (BlockingMock, Handle) {
+    let (mock, handle) = mock();
+    (BlockingMock::new(mock), handle)
+}
+
+/// Blocking counterpart to [`Mock`] that implements `std::io::Read` and `std::io::Write`
+///
+/// Each operation drives the wrapped Mock on the calling thread, consuming the same actions and
+/// producing the same events. When the Mock has nothing to offer, the thread blocks until the
+/// Handle queues an action or the optional timeout elapses. Waits queued with the `time` feature
+/// require a Tokio runtime with a timer.
+pub struct BlockingMock {
+    mock: Mock,
+    timeout: Option<Duration>,
+}
+
+struct ThreadWaker(std::thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+impl BlockingMock {
+    /// Wrap a Mock so that it can be used from blocking code
+    pub fn new(mock: Mock) -> Self {
+        Self {
+            mock,
+            timeout: None,
+        }
+    }
+
+    /// Limit how long an operation blocks, or block indefinitely with `None`
+    ///
+    /// An operation that doesn't complete within the timeout fails with `TimedOut`
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Access the wrapped Mock to configure it
+    pub fn get_mut(&mut self) -> &mut Mock {
+        &mut self.mock
+    }
+
+    /// Unwrap the Mock
+    pub fn into_inner(self) -> Mock {
+        self.mock
+    }
+
+    fn block_on<T, F>(&mut self, mut poll: F) -> Result<T, Error>
+    where
+        F: FnMut(Pin<&mut Mock>, &mut Context<'_>) -> Poll<Result<T, Error>>,
+    {
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let deadline = self.timeout.map(|x| Instant::now() + x);
+        loop {
+            if let Poll::Ready(x) = poll(Pin::new(&mut self.mock), &mut cx) {
+                return x;
+            }
+            match deadline {
+                None => std::thread::park(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(Error::new(
+                            ErrorKind::TimedOut,
+                            "timed out waiting for the mock",
+                        ));
+                    }
+                    std::thread::park_timeout(deadline - now);
+                }
+            }
+        }
+    }
+}
+
+impl std::io::Read for BlockingMock {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut buf = ReadBuf::new(buf);
+        self.block_on(|mock, cx| mock.poll_read(cx, &mut buf))?;
+        Ok(buf.filled().len())
+    }
+}
+
+impl std::io::Write for BlockingMock {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.block_on(|mock, cx| mock.poll_write(cx, buf))
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.block_on(|mock, cx| mock.poll_flush(cx))
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, Mutex};
+
+use crate::chunking::Chunker;
+use crate::{Action, Chunking, Event, Handle, Matcher, Mock, OnHandleDrop, Shared};
+
+/// Builds a Mock from a script of actions queued up front
+///
+/// The resulting Mock consumes the script in order. It can be created stand-alone with
+/// [`build`](Builder::build), or alongside a [`Handle`] with
+/// [`build_with_handle`](Builder::build_with_handle) so that the script can be extended at runtime.
+#[derive(Default)]
+pub struct Builder {
+    actions: VecDeque<Action>,
+    strict_reads: bool,
+    chunking: Chunking,
+    coalesce_writes: bool,
+    write_vectored: bool,
+    on_handle_drop: OnHandleDrop,
+}
+
+impl Builder {
+    /// Create an empty Builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a read operation
+    pub fn read(&mut self, data: &[u8]) -> &mut Self {
+        self.push(Action::read(data))
+    }
+
+    /// Queue an expected write
+    pub fn write(&mut self, data: &[u8]) -> &mut Self {
+        self.push(Action::write(data))
+    }
+
+    /// Queue an expected write that is checked using a [`Matcher`]
+    pub fn write_matching(&mut self, matcher: Matcher) -> &mut Self {
+        self.push(Action::WriteMatch(matcher))
+    }
+
+    /// Queue an end-of-stream
+    pub fn read_eof(&mut self) -> &mut Self {
+        self.push(Action::Eof)
+    }
+
+    /// Queue a persistent end-of-stream
+    pub fn close_read(&mut self) -> &mut Self {
+        self.push(Action::CloseRead)
+    }
+
+    /// Queue a read error
+    pub fn read_error(&mut self, kind: ErrorKind) -> &mut Self {
+        self.push(Action::read_error(kind.into()))
+    }
+
+    /// Queue a read error that is returned verbatim
+    pub fn read_error_with(&mut self, err: Error) -> &mut Self {
+        self.push(Action::read_error(err))
+    }
+
+    /// Queue a write error
+    pub fn write_error(&mut self, kind: ErrorKind) -> &mut Self {
+        self.push(Action::write_error(kind.into()))
+    }
+
+    /// Queue a write error that is returned verbatim
+    pub fn write_error_with(&mut self, err: Error) -> &mut Self {
+        self.push(Action::write_error(err))
+    }
+
+    /// Queue a flush error
+    pub fn flush_error(&mut self, kind: ErrorKind) -> &mut Self {
+        self.push(Action::flush_error(kind.into()))
+    }
+
+    /// Queue a flush error that is returned verbatim
+    pub fn flush_error_with(&mut self, err: Error) -> &mut Self {
+        self.push(Action::flush_error(err))
+    }
+
+    /// Queue a shutdown error
+    pub fn shutdown_error(&mut self, kind: ErrorKind) -> &mut Self {
+        self.push(Action::shutdown_error(kind.into()))
+    }
+
+    /// Queue a shutdown error that is returned verbatim
+    pub fn shutdown_error_with(&mut self, err: Error) -> &mut Self {
+        self.push(Action::shutdown_error(err))
+    }
+
+    /// Queue the result of the next seek
+    pub fn seek(&mut self, position: u64) -> &mut Self {
+        self.push(Action::Seek(position))
+    }
+
+    /// Queue a seek error
+    pub fn seek_error(&mut self, kind: ErrorKind) -> &mut Self {
+        self.push(Action::seek_error(kind.into()))
+    }
+
+    /// Queue a seek error that is returned verbatim
+    pub fn seek_error_with(&mut self, err: Error) -> &mut Self {
+        self.push(Action::seek_error(err))
+    }
+
+    /// Queue a limit on the number of bytes accepted by the next write
+    pub fn write_limit(&mut self, max: usize) -> &mut Self {
+        self.push(Action::WriteLimit(max))
+    }
+
+    /// Queue a delay during which all operations on the Mock return `Pending`
+    #[cfg(feature = "time")]
+    pub fn wait(&mut self, duration: std::time::Duration) -> &mut Self {
+        self.push(Action::Wait(duration))
+    }
+
+    /// Require that each queued read fits entirely within the caller's buffer
+    pub fn strict_reads(&mut self, strict: bool) -> &mut Self {
+        self.strict_reads = strict;
+        self
+    }
+
+    /// Match expected writes against the concatenated stream of written bytes
+    pub fn coalesce_writes(&mut self, coalesce: bool) -> &mut Self {
+        self.coalesce_writes = coalesce;
+        self
+    }
+
+    /// Configure the answer to `is_write_vectored`
+    pub fn write_vectored(&mut self, vectored: bool) -> &mut Self {
+        self.write_vectored = vectored;
+        self
+    }
+
+    /// Configure how queued reads are split across calls to `poll_read`
+    pub fn read_chunking(&mut self, chunking: Chunking) -> &mut Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Configure how the Mock behaves once its Handle has been dropped
+    pub fn on_handle_drop(&mut self, behavior: OnHandleDrop) -> &mut Self {
+        self.on_handle_drop = behavior;
+        self
+    }
+
+    /// Build a Mock that runs the script without a Handle
+    ///
+    /// Once the script is complete, reads return end-of-stream and writes panic
+    pub fn build(&mut self) -> Mock {
+        self.build_mock(None, None)
+    }
+
+    /// Build a Mock that runs the script and a Handle that can extend it
+    pub fn build_with_handle(&mut self) -> (Mock, Handle) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mock = self.build_mock(Some(rx), Some(event_tx));
+        let handle = Handle {
+            tx,
+            rx: event_rx,
+            shared: mock.shared.clone(),
+            strict: false,
+        };
+        (mock, handle)
+    }
+
+    fn push(&mut self, action: Action) -> &mut Self {
+        self.actions.push_back(action);
+        self
+    }
+
+    fn build_mock(
+        &mut self,
+        rx: Option<tokio::sync::mpsc::UnboundedReceiver<Action>>,
+        tx: Option<tokio::sync::mpsc::UnboundedSender<Event>>,
+    ) -> Mock {
+        let actions = std::mem::take(&mut self.actions);
+        let shared = Shared {
+            outstanding: actions.iter().map(|x| x.to_string()).collect(),
+            ..Default::default()
+        };
+        Mock {
+            actions,
+            rx,
+            tx,
+            read_waker: None,
+            seek_waker: None,
+            seek: None,
+            position: 0,
+            strict_reads: self.strict_reads,
+            chunker: Chunker::new(self.chunking.clone()),
+            coalesce_writes: self.coalesce_writes,
+            write_vectored: self.write_vectored,
+            write_limit: None,
+            partial_match: Vec::new(),
+            read_closed: false,
+            on_handle_drop: self.on_handle_drop,
+            disconnected: false,
+            #[cfg(feature = "time")]
+            sleep: None,
+            #[cfg(feature = "time")]
+            wait_wakers: Vec::new(),
+            shared: Arc::new(Mutex::new(shared)),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+/// Controls how the Mock splits queued reads across calls to `poll_read`
+///
+/// Framing bugs often appear only when a message is split at an unlucky offset. A non-default
+/// chunking re-splits every queued read regardless of how much space the caller provides.
+/// The Mock's panic messages for unexpected writes and strict reads include its chunking (and any
+/// seed) so the split can be replayed.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum Chunking {
+    /// deliver as much of each queued read as fits in the caller's buffer
+    #[default]
+    Whole,
+    /// deliver each queued read in chunks of at most this many bytes
+    Fixed(usize),
+    /// deliver each queued read in chunks of random sizes generated from this seed
+    Random(u64),
+    /// deliver queued reads in chunks of these sizes, cycling through the list
+    Sizes(Vec<usize>),
+}
+
+impl Chunking {
+    /// Enumerate every way of splitting a read of `len` bytes into chunks
+    ///
+    /// There are 2^(len - 1) such splits, so this is only practical for short reads
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than 64, as the number of splits no longer fits in a `u64`
+    pub fn exhaustive(len: usize) -> impl Iterator<Item = Chunking> {
+        assert!(
+            len <= 64,
+            "cannot enumerate the splits of a {} byte read, the limit is 64 bytes",
+            len
+        );
+        let splits: u64 = 1 << len.saturating_sub(1);
+        (0..splits).map(move |mask| {
+            let mut sizes = Vec::new();
+            let mut size = 1;
+            for bit in 0..len.saturating_sub(1) {
+                if mask & (1 << bit) != 0 {
+                    sizes.push(size);
+                    size = 1;
+                } else {
+                    size += 1;
+                }
+            }
+            sizes.push(size);
+            Chunking::Sizes(sizes)
+        })
+    }
+}
+
+/// Tracks the state required to produce the chunk sizes for a Chunking
+pub(crate) struct Chunker {
+    chunking: Chunking,
+    state: u64,
+    index: usize,
+}
+
+impl Chunker {
+    pub(crate) fn new(chunking: Chunking) -> Self {
+        let state = match chunking {
+            Chunking::Random(seed) => seed,
+            _ => 0,
+        };
+        Self {
+            chunking,
+            state,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn chunking(&self) -> &Chunking {
+        &self.chunking
+    }
+
+    /// Number of bytes to deliver next from a queued read with `len` bytes remaining
+    pub(crate) fn next(&mut self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        let size = match &self.chunking {
+            Chunking::Whole => len,
+            Chunking::Fixed(size) => *size,
+            Chunking::Random(_) => 1 + (self.random() % len as u64) as usize,
+            Chunking::Sizes(sizes) => match sizes.get(self.index % sizes.len().max(1)) {
+                Some(size) => {
+                    self.index += 1;
+                    *size
+                }
+                None => len,
+            },
+        };
+        size.clamp(1, len)
+    }
+
+    // splitmix64
+    fn random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
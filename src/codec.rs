@@ -0,0 +1,95 @@
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+use crate::{mock, Event, Handle, Mock};
+
+/// Create a framed Mock for the code under test and a FrameHandle that scripts it with typed frames
+///
+/// `codec` is the codec used by the code under test. `peer` is the codec used by the test to encode
+/// the frames the code under test reads and to decode the frames it writes.
+pub fn framed<C, P>(codec: C, peer: P) -> (Framed<Mock, C>, FrameHandle<P>) {
+    let (mock, handle) = mock();
+    (Framed::new(mock, codec), FrameHandle::new(handle, peer))
+}
+
+/// Wraps a [`Handle`] so that the test can queue and observe frames instead of raw bytes
+///
+/// The byte-level Handle remains accessible so that chunking, errors and other actions can still be
+/// scripted between frames.
+pub struct FrameHandle<P> {
+    handle: Handle,
+    peer: P,
+    // bytes written by the code under test that don't yet form a complete frame
+    written: BytesMut,
+}
+
+/// Events produced by a [`FrameHandle`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameEvent<T> {
+    /// a complete frame was written by the code under test
+    Frame(T),
+    /// any other event produced by the Mock
+    Event(Event),
+}
+
+impl<P> FrameHandle<P> {
+    /// Wrap a Handle using `peer` to encode and decode frames
+    pub fn new(handle: Handle, peer: P) -> Self {
+        Self {
+            handle,
+            peer,
+            written: BytesMut::new(),
+        }
+    }
+
+    /// Access the underlying byte-level Handle
+    pub fn handle(&mut self) -> &mut Handle {
+        &mut self.handle
+    }
+
+    /// Encode a frame and queue it as a read on the Mock
+    pub fn read_frame<I>(&mut self, frame: I) -> Result<(), P::Error>
+    where
+        P: Encoder<I>,
+    {
+        let mut bytes = BytesMut::new();
+        self.peer.encode(frame, &mut bytes)?;
+        self.handle.read(&bytes);
+        Ok(())
+    }
+
+    /// Encode a frame and queue it as an expected write on the Mock
+    pub fn write_frame<I>(&mut self, frame: I) -> Result<(), P::Error>
+    where
+        P: Encoder<I>,
+    {
+        let mut bytes = BytesMut::new();
+        self.peer.encode(frame, &mut bytes)?;
+        self.handle.write(&bytes);
+        Ok(())
+    }
+
+    /// Asynchronously wait for the next frame written by the code under test or other event
+    ///
+    /// Writes are accumulated until they contain a complete frame, regardless of how the code under
+    /// test split them across calls.
+    pub async fn next_frame(&mut self) -> Result<FrameEvent<P::Item>, P::Error>
+    where
+        P: Decoder,
+    {
+        loop {
+            if let Some(frame) = self.peer.decode(&mut self.written)? {
+                return Ok(FrameEvent::Frame(frame));
+            }
+            match self.handle.next_event().await {
+                Event::Write(bytes) => self.written.extend_from_slice(&bytes),
+                Event::WriteVectored(chunks) => {
+                    for chunk in chunks {
+                        self.written.extend_from_slice(&chunk);
+                    }
+                }
+                event => return Ok(FrameEvent::Event(event)),
+            }
+        }
+    }
+}
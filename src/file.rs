@@ -0,0 +1,199 @@
+use std::io::{Error, ErrorKind, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::ReadBuf;
+
+use crate::{mock, Action, Event, Handle, Mock};
+
+/// Create a MockFile holding `contents` and a controlling Handle
+pub fn mock_file(contents: &[u8]) -> (MockFile, Handle) {
+    let (mock, handle) = mock();
+    (MockFile::new(mock, contents), handle)
+}
+
+/// File-like counterpart to [`Mock`] backed by an in-memory buffer
+///
+/// Reads are served from the buffer at the current position, so reads after writes observe the
+/// written data. Writes are checked against the wrapped Mock's queued writes as usual before they
+/// are applied to the buffer. Queued read, write and seek errors are returned as usual, and a
+/// queued seek result overrides the position that the seek would otherwise reach.
+pub struct MockFile {
+    mock: Mock,
+    data: Vec<u8>,
+    // writes that would grow the file beyond this size fail like they would on a full disk
+    max_size: usize,
+}
+
+/// Default limit on the size of a MockFile, which stops a stray seek from allocating huge buffers
+const DEFAULT_MAX_SIZE: usize = 1 << 30;
+
+impl MockFile {
+    /// Wrap a Mock so that it behaves like a file holding `contents`
+    pub fn new(mock: Mock, contents: &[u8]) -> Self {
+        Self {
+            mock,
+            data: contents.to_vec(),
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+
+    /// Limit the size the file can grow to, which defaults to 1 GiB
+    ///
+    /// A write that starts at or beyond the limit fails with an `Other` error, and a write that
+    /// crosses it is cut short
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    /// Current contents of the file
+    pub fn contents(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Access the wrapped Mock to configure it
+    pub fn get_mut(&mut self) -> &mut Mock {
+        &mut self.mock
+    }
+
+    /// Unwrap the Mock, discarding the contents
+    pub fn into_inner(self) -> Mock {
+        self.mock
+    }
+
+    /// Position reached by seeking to `target`
+    fn resolve(&self, target: SeekFrom) -> std::io::Result<u64> {
+        let position = match target {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.data.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.mock.position.checked_add_signed(offset),
+        };
+        position.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })
+    }
+}
+
+impl tokio::io::AsyncRead for MockFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut ReadBuf,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if let Some(Action::ReadError(err)) = ready!(this.mock.front(cx)) {
+            let kind = err.kind();
+            this.mock.event(Event::ReadErr(kind));
+            return Poll::Ready(Err(this.mock.pop_error()));
+        }
+
+        let start = usize::try_from(this.mock.position)
+            .unwrap_or(usize::MAX)
+            .min(this.data.len());
+        let count = buf.remaining().min(this.data.len() - start);
+        if count == 0 && buf.remaining() > 0 {
+            this.mock.event(Event::Eof);
+            return Poll::Ready(Ok(()));
+        }
+
+        buf.put_slice(&this.data[start..start + count]);
+        this.mock.position += count as u64;
+        this.mock.event(Event::Read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl tokio::io::AsyncWrite for MockFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Error>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return this.mock.write_data(cx, buf, None);
+        }
+
+        let start = match usize::try_from(this.mock.position) {
+            Ok(start) if start < this.max_size => start,
+            _ => {
+                this.mock.event(Event::WriteErr(ErrorKind::Other));
+                return Poll::Ready(Err(Error::other(
+                    "write beyond the maximum size of the mock file",
+                )));
+            }
+        };
+        let buf = &buf[..buf.len().min(this.max_size.saturating_sub(start))];
+        let count = ready!(this.mock.write_data(cx, buf, None))?;
+
+        // writing past the end fills the gap with zeros, just like a real file
+        let end = start + count;
+        if this.data.len() < end {
+            this.data.resize(end, 0);
+        }
+        this.data[start..end].copy_from_slice(&buf[..count]);
+        this.mock.position = end as u64;
+        Poll::Ready(Ok(count))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        tokio::io::AsyncWrite::poll_flush(Pin::new(&mut self.get_mut().mock), cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        tokio::io::AsyncWrite::poll_shutdown(Pin::new(&mut self.get_mut().mock), cx)
+    }
+}
+
+impl tokio::io::AsyncSeek for MockFile {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        self.get_mut().mock.seek = Some(position);
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        let target = match this.mock.seek {
+            Some(target) => target,
+            None => return Poll::Ready(Ok(this.mock.position)),
+        };
+
+        ready!(this.mock.poll_actions(cx));
+        if this.mock.disconnected {
+            this.mock.seek = None;
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        let position = match ready!(this.mock.front(cx)) {
+            Some(Action::Seek(position)) => {
+                let position = *position;
+                this.mock.pop_front();
+                position
+            }
+            Some(Action::SeekError(err)) => {
+                let kind = err.kind();
+                this.mock.seek = None;
+                this.mock.event(Event::SeekErr(kind));
+                return Poll::Ready(Err(this.mock.pop_error()));
+            }
+            _ => {
+                this.mock.seek = None;
+                match this.resolve(target) {
+                    Ok(position) => position,
+                    Err(err) => {
+                        this.mock.event(Event::SeekErr(err.kind()));
+                        return Poll::Ready(Err(err));
+                    }
+                }
+            }
+        };
+
+        this.mock.seek = None;
+        this.mock.position = position;
+        this.mock.event(Event::Seek(target));
+        Poll::Ready(Ok(position))
+    }
+}
@@ -49,55 +49,371 @@ clippy::all
 )]
 
 use std::collections::VecDeque;
-use std::io::{Error, ErrorKind};
+use std::io::{Error, ErrorKind, IoSlice, SeekFrom};
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll, Waker};
 
 use tokio::io::ReadBuf;
 
+use crate::chunking::Chunker;
+
+mod blocking;
+mod builder;
+mod chunking;
+#[cfg(feature = "codec")]
+mod codec;
+mod file;
+#[cfg(feature = "futures-io")]
+mod futures;
+mod matcher;
+mod pair;
+
+pub use blocking::{blocking_mock, BlockingMock};
+pub use builder::Builder;
+pub use chunking::Chunking;
+#[cfg(feature = "codec")]
+pub use codec::{framed, FrameEvent, FrameHandle};
+pub use file::{mock_file, MockFile};
+pub use matcher::{Matcher, Predicate};
+pub use pair::{pair, Endpoint, Side, Tap};
+
 /// Create a Mock I/O object and a controlling Handle
 pub fn mock() -> (Mock, Handle) {
-    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-    let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel();
-    let mock = Mock {
-        actions: Default::default(),
-        rx,
-        tx: event_tx,
-    };
-    let handle = Handle { tx, rx: event_rx };
-    (mock, handle)
+    Builder::new().build_with_handle()
 }
 
 /// Mock object that can be used in lieu of a socket, etc
 pub struct Mock {
     // current queue of expected actions
     actions: VecDeque<Action>,
-    // how additional actions can be received
-    rx: tokio::sync::mpsc::UnboundedReceiver<Action>,
-    // how events get pushed back to the test
-    tx: tokio::sync::mpsc::UnboundedSender<Event>,
+    // how additional actions can be received, if there is a Handle
+    rx: Option<tokio::sync::mpsc::UnboundedReceiver<Action>>,
+    // how events get pushed back to the test, if there is a Handle
+    tx: Option<tokio::sync::mpsc::UnboundedSender<Event>>,
+    // reader parked behind an action for the write side
+    read_waker: Option<Waker>,
+    // seek parked behind an action for another operation
+    seek_waker: Option<Waker>,
+    // seek started by start_seek and awaiting completion
+    seek: Option<SeekFrom>,
+    // position returned by the last successful seek
+    position: u64,
+    // panic instead of performing a partial read
+    strict_reads: bool,
+    // determines how queued reads are split across calls to poll_read
+    chunker: Chunker,
+    // match expected writes against the concatenated write stream
+    coalesce_writes: bool,
+    // answer to is_write_vectored, enabling vectored writes
+    write_vectored: bool,
+    // limit consumed from the queue for a write that has yet to be performed
+    write_limit: Option<usize>,
+    // bytes of short writes that the matcher at the front of the queue checks along with the retry
+    partial_match: Vec<u8>,
+    // every read returns end-of-stream
+    read_closed: bool,
+    // what to do once the Handle is dropped
+    on_handle_drop: OnHandleDrop,
+    // the Handle was dropped and the Mock now behaves like a closed peer
+    disconnected: bool,
+    // timer for the wait at the front of the queue
+    #[cfg(feature = "time")]
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+    // every operation parked behind the wait, since the timer only wakes the last one
+    #[cfg(feature = "time")]
+    wait_wakers: Vec<Waker>,
+    // state that the test manipulates directly rather than via queued actions
+    shared: Arc<Mutex<Shared>>,
 }
 
 /// Handle which can send actions to the Mock and monitor Event's as the mock consumes the actions
 pub struct Handle {
     tx: tokio::sync::mpsc::UnboundedSender<Action>,
     rx: tokio::sync::mpsc::UnboundedReceiver<Event>,
+    shared: Arc<Mutex<Shared>>,
+    // panic on drop if there are unread events
+    strict: bool,
+}
+
+/// State shared between the Mock and the Handle that takes effect immediately
+#[derive(Default)]
+struct Shared {
+    write: Gate,
+    flush: Gate,
+    shutdown: Gate,
+    write_buffer: WriteBuffer,
+    // descriptions of the actions sent by the Handle that the Mock has not yet consumed
+    outstanding: VecDeque<String>,
+}
+
+/// Holds write, flush or shutdown calls until the test releases them
+#[derive(Default)]
+struct Gate {
+    held: bool,
+    waker: Option<Waker>,
+}
+
+impl Gate {
+    fn hold(&mut self) {
+        self.held = true;
+    }
+
+    fn release(&mut self) {
+        self.held = false;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Returns true if the caller must wait, parking its waker
+    fn wait(&mut self, cx: &mut Context) -> bool {
+        if self.held {
+            self.waker = Some(cx.waker().clone());
+        }
+        self.held
+    }
+}
+
+/// Models the send buffer of a socket that the test drains
+#[derive(Default)]
+struct WriteBuffer {
+    capacity: Option<usize>,
+    used: usize,
+    waker: Option<Waker>,
+}
+
+impl WriteBuffer {
+    fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        self.wake();
+    }
+
+    fn drain(&mut self, count: usize) {
+        self.used = self.used.saturating_sub(count);
+        self.wake();
+    }
+
+    /// Returns the number of bytes that may be written, parking the waker if there is no space
+    fn available(&mut self, cx: &mut Context) -> Option<usize> {
+        let available = self
+            .capacity
+            .map(|capacity| capacity.saturating_sub(self.used));
+        if available == Some(0) {
+            self.waker = Some(cx.waker().clone());
+        }
+        available
+    }
+
+    fn consume(&mut self, count: usize) {
+        if self.capacity.is_some() {
+            self.used += count;
+        }
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
 }
 
 impl Handle {
+    /// Require that every event produced by the Mock is read before the Handle is dropped
+    ///
+    /// In strict mode, dropping the Handle with unread events panics and lists them
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
     /// Queue a read operation on the Mock
     pub fn read(&mut self, data: &[u8]) {
-        self.tx.send(Action::read(data)).unwrap()
+        self.send(Action::read(data))
+    }
+
+    /// Queue an expected write on the Mock
+    ///
+    /// The next write performed on the Mock must match these bytes exactly or the Mock panics
+    pub fn write(&mut self, data: &[u8]) {
+        self.send(Action::write(data))
+    }
+
+    /// Queue an expected write on the Mock that is checked using a [`Matcher`]
+    ///
+    /// Matchers are checked against a whole write call, or when coalescing writes, against the rest
+    /// of the call after any preceding expected writes. A write cut short by a write limit is
+    /// checked as far as it goes and completed by the retries; suffix and custom matchers are
+    /// checked once a retry is no longer cut short.
+    pub fn write_matching(&mut self, matcher: Matcher) {
+        self.send(Action::WriteMatch(matcher))
+    }
+
+    /// Queue an end-of-stream on the Mock
+    ///
+    /// The next read returns zero bytes, after which the Mock resumes consuming queued actions
+    pub fn read_eof(&mut self) {
+        self.send(Action::Eof)
+    }
+
+    /// Queue a persistent end-of-stream on the Mock
+    ///
+    /// The next read and every read thereafter return zero bytes, like a half-closed TCP stream
+    pub fn close_read(&mut self) {
+        self.send(Action::CloseRead)
     }
 
     /// Queue a read error on the Mock
     pub fn read_error(&mut self, kind: ErrorKind) {
-        self.tx.send(Action::read_error(kind)).unwrap()
+        self.send(Action::read_error(kind.into()))
+    }
+
+    /// Queue a read error on the Mock that returns `err` verbatim
+    ///
+    /// Like the other `*_error_with` methods, this preserves custom messages, inner errors and raw
+    /// OS error codes
+    pub fn read_error_with(&mut self, err: Error) {
+        self.send(Action::read_error(err))
     }
 
     /// Queue a write error on the Mock
     pub fn write_error(&mut self, kind: ErrorKind) {
-        self.tx.send(Action::write_error(kind)).unwrap()
+        self.send(Action::write_error(kind.into()))
+    }
+
+    /// Queue a write error on the Mock that returns `err` verbatim
+    pub fn write_error_with(&mut self, err: Error) {
+        self.send(Action::write_error(err))
+    }
+
+    /// Queue a flush error on the Mock
+    pub fn flush_error(&mut self, kind: ErrorKind) {
+        self.send(Action::flush_error(kind.into()))
+    }
+
+    /// Queue a flush error on the Mock that returns `err` verbatim
+    pub fn flush_error_with(&mut self, err: Error) {
+        self.send(Action::flush_error(err))
+    }
+
+    /// Queue a shutdown error on the Mock
+    pub fn shutdown_error(&mut self, kind: ErrorKind) {
+        self.send(Action::shutdown_error(kind.into()))
+    }
+
+    /// Queue a shutdown error on the Mock that returns `err` verbatim
+    pub fn shutdown_error_with(&mut self, err: Error) {
+        self.send(Action::shutdown_error(err))
+    }
+
+    /// Queue the result of the next seek on the Mock
+    pub fn seek(&mut self, position: u64) {
+        self.send(Action::Seek(position))
+    }
+
+    /// Queue a seek error on the Mock
+    pub fn seek_error(&mut self, kind: ErrorKind) {
+        self.send(Action::seek_error(kind.into()))
+    }
+
+    /// Queue a seek error on the Mock that returns `err` verbatim
+    pub fn seek_error_with(&mut self, err: Error) {
+        self.send(Action::seek_error(err))
+    }
+
+    /// Queue a limit on the number of bytes accepted by the next write on the Mock
+    ///
+    /// A queued write that is cut short is matched against the bytes that were accepted, and the
+    /// rest of it is expected from the next write
+    pub fn write_limit(&mut self, max: usize) {
+        self.send(Action::WriteLimit(max))
+    }
+
+    /// Queue a delay on the Mock
+    ///
+    /// Once the delay reaches the front of the queue, all operations on the Mock return `Pending`
+    /// until it elapses. Under `tokio::time::pause()` this is fully deterministic.
+    #[cfg(feature = "time")]
+    pub fn wait(&mut self, duration: std::time::Duration) {
+        self.send(Action::Wait(duration))
+    }
+
+    /// Hold write calls on the Mock until `release_write` is called
+    ///
+    /// While held, `poll_write` returns `Pending` without consuming any actions
+    pub fn hold_write(&mut self) {
+        self.shared.lock().unwrap().write.hold();
+    }
+
+    /// Release held write calls, waking any task waiting on a write
+    pub fn release_write(&mut self) {
+        self.shared.lock().unwrap().write.release();
+    }
+
+    /// Limit the Mock to a send buffer of `capacity` bytes, or remove the limit with `None`
+    ///
+    /// Writes accept only as many bytes as there is space in the buffer and return `Pending`
+    /// when it is full. Space is freed with `drain_write_buffer`.
+    pub fn set_write_buffer(&mut self, capacity: Option<usize>) {
+        self.shared
+            .lock()
+            .unwrap()
+            .write_buffer
+            .set_capacity(capacity);
+    }
+
+    /// Free `count` bytes of space in the send buffer, waking any task waiting on a write
+    pub fn drain_write_buffer(&mut self, count: usize) {
+        self.shared.lock().unwrap().write_buffer.drain(count);
+    }
+
+    /// Hold flush calls on the Mock until `release_flush` is called
+    ///
+    /// While held, `poll_flush` returns `Pending` without consuming any actions
+    pub fn hold_flush(&mut self) {
+        self.shared.lock().unwrap().flush.hold();
+    }
+
+    /// Release held flush calls, waking any task waiting on a flush
+    pub fn release_flush(&mut self) {
+        self.shared.lock().unwrap().flush.release();
+    }
+
+    /// Hold shutdown calls on the Mock until `release_shutdown` is called
+    ///
+    /// While held, `poll_shutdown` returns `Pending` without consuming any actions
+    pub fn hold_shutdown(&mut self) {
+        self.shared.lock().unwrap().shutdown.hold();
+    }
+
+    /// Release held shutdown calls, waking any task waiting on a shutdown
+    pub fn release_shutdown(&mut self) {
+        self.shared.lock().unwrap().shutdown.release();
+    }
+
+    /// Check that the Mock has consumed every queued action and that every event has been read
+    ///
+    /// Any unread events are removed from the Handle and returned in the error
+    pub fn verify(&mut self) -> Result<(), VerifyError> {
+        let actions: Vec<String> = self
+            .shared
+            .lock()
+            .unwrap()
+            .outstanding
+            .iter()
+            .cloned()
+            .collect();
+        let mut events = Vec::new();
+        while let Ok(event) = self.rx.try_recv() {
+            events.push(event);
+        }
+        VerifyError::check(actions, events)
+    }
+
+    /// Panic if the Mock has not consumed every queued action or an event has not been read
+    pub fn assert_idle(&mut self) {
+        if let Err(err) = self.verify() {
+            panic!("{}", err)
+        }
     }
 
     /// Asynchronously wait for the next event
@@ -105,18 +421,104 @@ impl Handle {
         self.rx.recv().await.unwrap()
     }
 
+    /// Block the current thread until the next event
+    ///
+    /// Intended for use with a [`BlockingMock`]. Panics if called from within an asynchronous context.
+    pub fn blocking_next_event(&mut self) -> Event {
+        self.rx.blocking_recv().unwrap()
+    }
+
     /// Pop the next event if present
     pub fn pop_event(&mut self) -> Option<Event> {
         self.rx.try_recv().ok()
     }
+
+    fn send(&mut self, action: Action) {
+        self.shared
+            .lock()
+            .unwrap()
+            .outstanding
+            .push_back(action.to_string());
+        self.tx.send(action).unwrap()
+    }
+}
+
+/// Describes the actions and events that were outstanding when verifying a Mock or Handle
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyError {
+    /// descriptions of queued actions that were not consumed, in order
+    pub actions: Vec<String>,
+    /// events that were produced but not read, in order
+    pub events: Vec<Event>,
+}
+
+impl VerifyError {
+    fn check(actions: Vec<String>, events: Vec<Event>) -> Result<(), Self> {
+        if actions.is_empty() && events.is_empty() {
+            Ok(())
+        } else {
+            Err(Self { actions, events })
+        }
+    }
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("mock is not idle")?;
+        if !self.actions.is_empty() {
+            write!(
+                f,
+                "\n{} unused mock action(s):\n{}",
+                self.actions.len(),
+                list(self.actions.iter())
+            )?;
+        }
+        if !self.events.is_empty() {
+            write!(
+                f,
+                "\n{} unread mock event(s):\n{}",
+                self.events.len(),
+                list(self.events.iter())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Determines how the Mock behaves once its Handle has been dropped
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum OnHandleDrop {
+    /// keep consuming the actions that were already queued, discarding events, then panic as soon
+    /// as the Mock needs an action and none are queued; flushes and shutdowns still succeed
+    #[default]
+    Panic,
+    /// discard any queued actions and behave like a closed peer: reads return end-of-stream,
+    /// writes, flushes, shutdowns and seeks fail with `BrokenPipe`
+    Disconnect,
+    /// keep consuming the actions that were already queued, discarding events, then behave like a
+    /// Mock built without a Handle
+    Continue,
 }
 
 /// events are things we queue up for the component under test
 #[derive(Debug)]
 enum Action {
     Read(Vec<u8>),
-    ReadError(ErrorKind),
-    WriteError(ErrorKind),
+    Write(Vec<u8>),
+    WriteMatch(Matcher),
+    WriteLimit(usize),
+    #[cfg(feature = "time")]
+    Wait(std::time::Duration),
+    Eof,
+    CloseRead,
+    ReadError(Error),
+    WriteError(Error),
+    FlushError(Error),
+    ShutdownError(Error),
+    Seek(u64),
+    SeekError(Error),
 }
 
 /// Events that is produced as the Mock consumes an action
@@ -124,12 +526,28 @@ enum Action {
 pub enum Event {
     /// write operation was performed
     Write(Vec<u8>),
+    /// vectored write operation was performed, with the bytes accepted from each of the caller's slices
+    WriteVectored(Vec<Vec<u8>>),
     /// all of the data in a queued read was consumed
     Read,
+    /// queued end-of-stream was returned by the mock
+    Eof,
     /// queued write error was returned by the mock
-    WriteErr,
+    WriteErr(ErrorKind),
     /// queued read error was returned by the mock
-    ReadErr,
+    ReadErr(ErrorKind),
+    /// flush operation was performed
+    Flush,
+    /// queued flush error was returned by the mock
+    FlushErr(ErrorKind),
+    /// shutdown operation was performed
+    Shutdown,
+    /// queued shutdown error was returned by the mock
+    ShutdownErr(ErrorKind),
+    /// seek operation was performed
+    Seek(SeekFrom),
+    /// queued seek error was returned by the mock
+    SeekErr(ErrorKind),
 }
 
 impl Action {
@@ -137,76 +555,659 @@ impl Action {
         Self::Read(data.to_vec())
     }
 
-    fn read_error(kind: ErrorKind) -> Self {
-        Self::ReadError(kind)
+    fn write(data: &[u8]) -> Self {
+        Self::Write(data.to_vec())
+    }
+
+    fn read_error(err: Error) -> Self {
+        Self::ReadError(err)
+    }
+
+    fn write_error(err: Error) -> Self {
+        Self::WriteError(err)
+    }
+
+    fn flush_error(err: Error) -> Self {
+        Self::FlushError(err)
+    }
+
+    fn shutdown_error(err: Error) -> Self {
+        Self::ShutdownError(err)
+    }
+
+    fn seek_error(err: Error) -> Self {
+        Self::SeekError(err)
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Read(bytes) => write!(f, "read ({} bytes): [{}]", bytes.len(), hex(bytes)),
+            Action::Write(bytes) => write!(f, "write ({} bytes): [{}]", bytes.len(), hex(bytes)),
+            Action::WriteMatch(matcher) => write!(f, "write matching {}", matcher),
+            Action::WriteLimit(max) => write!(f, "write limit: {} bytes", max),
+            #[cfg(feature = "time")]
+            Action::Wait(duration) => write!(f, "wait: {:?}", duration),
+            Action::Eof => f.write_str("end-of-stream"),
+            Action::CloseRead => f.write_str("close read"),
+            Action::ReadError(err) => write!(f, "read error: {}", describe(err)),
+            Action::WriteError(err) => write!(f, "write error: {}", describe(err)),
+            Action::FlushError(err) => write!(f, "flush error: {}", describe(err)),
+            Action::ShutdownError(err) => write!(f, "shutdown error: {}", describe(err)),
+            Action::Seek(position) => write!(f, "seek to: {}", position),
+            Action::SeekError(err) => write!(f, "seek error: {}", describe(err)),
+        }
     }
+}
 
-    fn write_error(kind: ErrorKind) -> Self {
-        Self::WriteError(kind)
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::Write(bytes) => write!(f, "write ({} bytes): [{}]", bytes.len(), hex(bytes)),
+            Event::WriteVectored(chunks) => write!(
+                f,
+                "vectored write ({} bytes): {}",
+                chunks.iter().map(|x| x.len()).sum::<usize>(),
+                chunks
+                    .iter()
+                    .map(|x| format!("[{}]", hex(x)))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Event::Read => f.write_str("read"),
+            Event::Eof => f.write_str("end-of-stream"),
+            Event::WriteErr(kind) => write!(f, "write error: {:?}", kind),
+            Event::ReadErr(kind) => write!(f, "read error: {:?}", kind),
+            Event::Flush => f.write_str("flush"),
+            Event::FlushErr(kind) => write!(f, "flush error: {:?}", kind),
+            Event::Shutdown => f.write_str("shutdown"),
+            Event::ShutdownErr(kind) => write!(f, "shutdown error: {:?}", kind),
+            Event::Seek(position) => write!(f, "seek: {:?}", position),
+            Event::SeekErr(kind) => write!(f, "seek error: {:?}", kind),
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        if !self.strict || std::thread::panicking() {
+            return;
+        }
+        let mut events = Vec::new();
+        while let Ok(event) = self.rx.try_recv() {
+            events.push(event);
+        }
+        if !events.is_empty() {
+            panic!(
+                "{} unread mock event(s):\n{}",
+                events.len(),
+                list(events.iter())
+            )
+        }
     }
 }
 
 impl Drop for Mock {
     fn drop(&mut self) {
-        self.rx.close();
-        if let Ok(action) = self.rx.try_recv() {
-            if !std::thread::panicking() {
-                panic!("Unused mock action: {:?}", action)
+        if let Some(rx) = self.rx.as_mut() {
+            rx.close();
+            while let Ok(action) = rx.try_recv() {
+                self.actions.push_back(action);
             }
         }
+        if std::thread::panicking() {
+            return;
+        }
+        if !self.actions.is_empty() {
+            panic!(
+                "{} unused mock action(s):\n{}",
+                self.actions.len(),
+                list(self.actions.iter())
+            )
+        }
     }
 }
 
+fn list<T: std::fmt::Display>(items: impl Iterator<Item = T>) -> String {
+    items
+        .enumerate()
+        .map(|(i, x)| format!("  {}: {}", i + 1, x))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 impl Mock {
-    fn front(&mut self, cx: &mut Context) -> Option<&Action> {
-        // we always poll the receiver
-        if let Poll::Ready(action) = self.rx.poll_recv(cx) {
-            match action {
-                None => {
+    /// Require that each queued read fits entirely within the caller's buffer
+    ///
+    /// By default, a queued read that doesn't fit is delivered across multiple calls to `poll_read`.
+    /// In strict mode the Mock panics instead.
+    pub fn set_strict_reads(&mut self, strict: bool) {
+        self.strict_reads = strict;
+    }
+
+    /// Match expected writes against the concatenated stream of written bytes
+    ///
+    /// By default, each write must match the expected write at the front of the queue exactly.
+    /// When coalescing, the bytes of consecutive expected writes may be written in any number of
+    /// calls. [`Event::Write`] still reports each call as it was made.
+    pub fn set_coalesce_writes(&mut self, coalesce: bool) {
+        self.coalesce_writes = coalesce;
+    }
+
+    /// Configure the answer to `is_write_vectored`
+    ///
+    /// When enabled, vectored writes are handled as a single write of all the caller's slices and
+    /// reported as [`Event::WriteVectored`]. Otherwise, like the default implementation, only the
+    /// first non-empty slice is written.
+    pub fn set_write_vectored(&mut self, vectored: bool) {
+        self.write_vectored = vectored;
+    }
+
+    /// Configure how queued reads are split across calls to `poll_read`
+    pub fn set_read_chunking(&mut self, chunking: Chunking) {
+        self.chunker = Chunker::new(chunking);
+    }
+
+    /// Configure how the Mock behaves once its Handle has been dropped
+    pub fn set_on_handle_drop(&mut self, behavior: OnHandleDrop) {
+        self.on_handle_drop = behavior;
+    }
+
+    /// Check that every queued action has been consumed
+    pub fn verify(&mut self) -> Result<(), VerifyError> {
+        if let Some(rx) = self.rx.as_mut() {
+            while let Ok(action) = rx.try_recv() {
+                self.actions.push_back(action);
+            }
+        }
+        VerifyError::check(
+            self.actions.iter().map(|x| x.to_string()).collect(),
+            Vec::new(),
+        )
+    }
+
+    fn front(&mut self, cx: &mut Context) -> Poll<Option<&mut Action>> {
+        ready!(self.poll_actions(cx));
+        Poll::Ready(self.actions.front_mut())
+    }
+
+    fn poll_actions(&mut self, cx: &mut Context) -> Poll<()> {
+        self.poll_queue(cx, true)
+    }
+
+    /// Like `poll_actions`, for operations that succeed when no action is queued
+    fn poll_optional_actions(&mut self, cx: &mut Context) -> Poll<()> {
+        self.poll_queue(cx, false)
+    }
+
+    fn poll_queue(&mut self, cx: &mut Context, needed: bool) -> Poll<()> {
+        // we always drain the receiver so that the queue reflects every action sent so far
+        let mut closed = false;
+        if let Some(rx) = self.rx.as_mut() {
+            while let Poll::Ready(action) = rx.poll_recv(cx) {
+                match action {
+                    None => {
+                        closed = true;
+                        break;
+                    }
+                    Some(x) => {
+                        self.actions.push_back(x);
+                    }
+                }
+            }
+        }
+
+        if closed {
+            self.on_handle_dropped(needed);
+        }
+
+        #[cfg(feature = "time")]
+        ready!(self.poll_wait(cx));
+
+        Poll::Ready(())
+    }
+
+    /// Consume any waits at the front of the queue once they have elapsed
+    #[cfg(feature = "time")]
+    fn poll_wait(&mut self, cx: &mut Context) -> Poll<()> {
+        use std::future::Future;
+
+        while let Some(Action::Wait(duration)) = self.actions.front() {
+            let duration = *duration;
+            let sleep = self
+                .sleep
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(duration)));
+            if sleep.as_mut().poll(cx).is_pending() {
+                if !self.wait_wakers.iter().any(|x| x.will_wake(cx.waker())) {
+                    self.wait_wakers.push(cx.waker().clone());
+                }
+                return Poll::Pending;
+            }
+            self.sleep = None;
+            self.pop_front();
+            for waker in self.wait_wakers.drain(..) {
+                waker.wake();
+            }
+        }
+        Poll::Ready(())
+    }
+
+    fn on_handle_dropped(&mut self, needed: bool) {
+        match self.on_handle_drop {
+            OnHandleDrop::Panic => {
+                if needed && self.actions.is_empty() {
                     panic!("The sending side of the channel was closed");
                 }
-                Some(x) => {
-                    self.actions.push_back(x);
+            }
+            OnHandleDrop::Disconnect => {
+                self.rx = None;
+                self.tx = None;
+                self.disconnected = true;
+                self.read_closed = true;
+                self.actions.clear();
+                self.shared.lock().unwrap().outstanding.clear();
+            }
+            OnHandleDrop::Continue => {
+                self.rx = None;
+                self.tx = None;
+            }
+        }
+    }
+
+    /// True if every action has been consumed and there is no Handle to queue more
+    fn script_complete(&self) -> bool {
+        self.rx.is_none() && self.actions.is_empty()
+    }
+
+    fn event(&self, event: Event) {
+        if let Some(tx) = self.tx.as_ref() {
+            // the Handle may have been dropped while queued actions remain, in which case nobody is
+            // observing
+            let _ = tx.send(event);
+        }
+    }
+
+    fn pop_front(&mut self) -> Option<Action> {
+        let action = self.actions.pop_front();
+        self.shared.lock().unwrap().outstanding.pop_front();
+        // reads and seeks may have been waiting on the action we just consumed
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+        if let Some(waker) = self.seek_waker.take() {
+            waker.wake();
+        }
+        action
+    }
+
+    /// Pop the error action at the front of the queue, returning its error
+    fn pop_error(&mut self) -> Error {
+        match self.pop_front() {
+            Some(Action::ReadError(err))
+            | Some(Action::WriteError(err))
+            | Some(Action::FlushError(err))
+            | Some(Action::ShutdownError(err))
+            | Some(Action::SeekError(err)) => err,
+            action => unreachable!("expected an error action but found {:?}", action),
+        }
+    }
+
+    /// Handle the action at the front of the queue on behalf of a read
+    ///
+    /// Returns the size of the chunk of the queued read to deliver next, or `None` at end-of-stream
+    fn poll_read_front(&mut self, cx: &mut Context) -> Poll<std::io::Result<Option<usize>>> {
+        if self.read_closed {
+            return Poll::Ready(Ok(None));
+        }
+
+        let len = match ready!(self.front(cx)) {
+            None => {
+                if self.read_closed {
+                    // the Handle was dropped while this read was in progress
+                    return Poll::Ready(Ok(None));
+                }
+                if self.rx.is_none() {
+                    // nothing can extend the script, so it ends like the stream it describes
+                    return Poll::Ready(Ok(None));
+                }
+                return Poll::Pending;
+            }
+            Some(action) => match action {
+                Action::Read(bytes) => bytes.len(),
+                Action::Eof => {
+                    self.event(Event::Eof);
+                    self.pop_front();
+                    return Poll::Ready(Ok(None));
+                }
+                Action::CloseRead => {
+                    self.read_closed = true;
+                    self.event(Event::Eof);
+                    self.pop_front();
+                    return Poll::Ready(Ok(None));
+                }
+                Action::ReadError(err) => {
+                    let kind = err.kind();
+                    self.event(Event::ReadErr(kind));
+                    return Poll::Ready(Err(self.pop_error()));
                 }
+                Action::Write(_)
+                | Action::WriteMatch(_)
+                | Action::WriteLimit(_)
+                | Action::WriteError(_)
+                | Action::FlushError(_)
+                | Action::ShutdownError(_)
+                | Action::Seek(_)
+                | Action::SeekError(_) => {
+                    self.read_waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                #[cfg(feature = "time")]
+                Action::Wait(_) => unreachable!("waits are consumed by front()"),
+            },
+        };
+
+        Poll::Ready(Ok(Some(self.chunker.next(len))))
+    }
+
+    /// Consume bytes from the read at the front of the queue, completing it once it is empty
+    fn consume_read(&mut self, count: usize) {
+        if let Some(Action::Read(bytes)) = self.actions.front_mut() {
+            bytes.drain(..count.min(bytes.len()));
+            if bytes.is_empty() {
+                self.event(Event::Read);
+                self.pop_front();
+            } else {
+                self.refresh_front();
+            }
+        }
+    }
+
+    /// Describe what remains of a partly consumed action to `Handle::verify`
+    fn refresh_front(&mut self) {
+        if let Some(action) = self.actions.front() {
+            if let Some(x) = self.shared.lock().unwrap().outstanding.front_mut() {
+                *x = action.to_string();
+            }
+        }
+    }
+
+    fn write_data(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        slices: Option<&[IoSlice<'_>]>,
+    ) -> Poll<Result<usize, Error>> {
+        let available = {
+            let mut shared = self.shared.lock().unwrap();
+            if shared.write.wait(cx) {
+                return Poll::Pending;
+            }
+            match shared.write_buffer.available(cx) {
+                Some(0) => return Poll::Pending,
+                x => x,
+            }
+        };
+
+        let requested = buf.len();
+        let mut buf = match available {
+            Some(count) if count < buf.len() => &buf[..count],
+            _ => buf,
+        };
+
+        ready!(self.poll_actions(cx));
+        if self.disconnected {
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        if let Some(Action::WriteLimit(max)) = self.actions.front() {
+            self.write_limit = Some(*max);
+            self.pop_front();
+        }
+
+        // a wait may follow the limit, which must survive until the write is performed
+        ready!(self.poll_actions(cx));
+        if let Some(max) = self.write_limit.take() {
+            if max < buf.len() {
+                buf = &buf[..max];
+            }
+        }
+
+        if self.script_complete() {
+            unexpected_write(
+                0,
+                "nothing, the script is complete",
+                buf,
+                self.chunker.chunking(),
+            );
+        }
+
+        let coalesce_writes = self.coalesce_writes;
+        // matched directly against the queue so that mismatches can report the read chunking
+        let ret = match self.actions.front_mut() {
+            Some(Action::Write(_)) if coalesce_writes => {
+                self.event(write_event(buf, slices));
+                self.check_stream(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+            Some(Action::WriteError(err)) => {
+                let kind = err.kind();
+                self.event(Event::WriteErr(kind));
+                Poll::Ready(Err(self.pop_error()))
+            }
+            Some(Action::Write(expected))
+                if buf.len() < requested && buf.len() < expected.len() =>
+            {
+                // a short write consumes the start of the expected write, leaving the rest for the retry
+                check_write(&expected[..buf.len()], buf, self.chunker.chunking());
+                expected.drain(..buf.len());
+                self.refresh_front();
+                self.event(write_event(buf, slices));
+                Poll::Ready(Ok(buf.len()))
+            }
+            Some(Action::Write(expected)) => {
+                check_write(expected, buf, self.chunker.chunking());
+                self.event(write_event(buf, slices));
+                self.pop_front();
+                Poll::Ready(Ok(buf.len()))
+            }
+            Some(Action::WriteMatch(matcher)) => {
+                let mut data = std::mem::take(&mut self.partial_match);
+                data.extend_from_slice(buf);
+                if buf.len() < requested && matcher.needs_more(&data) {
+                    // a short write is checked as far as it goes, leaving the rest for the retry
+                    if let Some(position) = matcher.prefix_mismatch(&data) {
+                        unexpected_write(position, matcher, &data, self.chunker.chunking());
+                    }
+                    self.partial_match = data;
+                    self.event(write_event(buf, slices));
+                } else {
+                    check_match(matcher, &data, self.chunker.chunking());
+                    self.event(write_event(buf, slices));
+                    self.pop_front();
+                }
+                Poll::Ready(Ok(buf.len()))
+            }
+            _ => {
+                self.event(write_event(buf, slices));
+                Poll::Ready(Ok(buf.len()))
+            }
+        };
+
+        if let Poll::Ready(Ok(count)) = ret {
+            self.shared.lock().unwrap().write_buffer.consume(count);
+        }
+
+        ret
+    }
+
+    /// Match written bytes against consecutive expected writes, ignoring call boundaries
+    fn check_stream(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let expected = match self.actions.front_mut() {
+                Some(Action::Write(expected)) => expected,
+                _ => break,
+            };
+            let count = expected.len().min(data.len());
+            check_write(&expected[..count], &data[..count], self.chunker.chunking());
+            expected.drain(..count);
+            data = &data[count..];
+            if expected.is_empty() {
+                self.pop_front();
+            } else {
+                self.refresh_front();
             }
         }
 
-        self.actions.front()
+        // bytes beyond the expected writes must still satisfy whatever the script expects next
+        if data.is_empty() {
+            return;
+        }
+        if self.script_complete() {
+            unexpected_write(
+                0,
+                "nothing, the script is complete",
+                data,
+                self.chunker.chunking(),
+            );
+        }
+        if let Some(Action::WriteMatch(matcher)) = self.actions.front() {
+            check_match(matcher, data, self.chunker.chunking());
+            self.pop_front();
+        }
     }
 }
 
+fn check_write(expected: &[u8], actual: &[u8], chunking: &Chunking) {
+    if expected != actual {
+        let position = expected
+            .iter()
+            .zip(actual.iter())
+            .position(|(x, y)| x != y)
+            .unwrap_or_else(|| expected.len().min(actual.len()));
+        unexpected_write(
+            position,
+            format!("({} bytes): [{}]", expected.len(), hex(expected)),
+            actual,
+            chunking,
+        );
+    }
+}
+
+fn check_match(matcher: &Matcher, actual: &[u8], chunking: &Chunking) {
+    if let Some(position) = matcher.mismatch(actual) {
+        unexpected_write(position, matcher, actual, chunking);
+    }
+}
+
+fn unexpected_write(
+    position: usize,
+    expected: impl std::fmt::Display,
+    actual: &[u8],
+    chunking: &Chunking,
+) -> ! {
+    panic!(
+        "Unexpected write at byte {}\nexpected {}\n  actual ({} bytes): [{}]{}",
+        position,
+        expected,
+        actual.len(),
+        hex(actual),
+        chunking_note(chunking)
+    );
+}
+
+/// Describes a non-default chunking in panic messages so that a failing split can be replayed
+fn chunking_note(chunking: &Chunking) -> String {
+    match chunking {
+        Chunking::Whole => String::new(),
+        chunking => format!("\nread chunking: {:?}", chunking),
+    }
+}
+
+/// Event for a write of `data`, split into the caller's slices for vectored writes
+fn write_event(data: &[u8], slices: Option<&[IoSlice<'_>]>) -> Event {
+    match slices {
+        None => Event::Write(data.to_vec()),
+        Some(slices) => {
+            let mut remaining = data;
+            let mut chunks = Vec::new();
+            for slice in slices {
+                let (chunk, rest) = remaining.split_at(slice.len().min(remaining.len()));
+                chunks.push(chunk.to_vec());
+                remaining = rest;
+            }
+            Event::WriteVectored(chunks)
+        }
+    }
+}
+
+fn describe(err: &Error) -> String {
+    if err.get_ref().is_none() && err.raw_os_error().is_none() {
+        format!("{:?}", err.kind())
+    } else {
+        format!("{:?}", err)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|x| format!("{:02X}", x))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 impl tokio::io::AsyncRead for Mock {
     fn poll_read(
         mut self: Pin<&mut Self>,
         cx: &mut Context,
         buf: &mut ReadBuf,
     ) -> Poll<std::io::Result<()>> {
-        match self.front(cx) {
-            None => Poll::Pending,
-            Some(action) => match action {
-                Action::Read(bytes) => {
-                    if buf.remaining() < bytes.len() {
-                        panic!(
-                            "Expecting a read for at least {} bytes but only space for {} bytes",
-                            bytes.len(),
-                            buf.remaining()
-                        );
-                    }
-                    buf.put_slice(bytes.as_slice());
-                    self.tx.send(Event::Read).unwrap();
-                    self.actions.pop_front();
-                    Poll::Ready(Ok(()))
-                }
-                Action::ReadError(kind) => {
-                    let kind = *kind;
-                    let ret = Poll::Ready(Err(kind.into()));
-                    self.tx.send(Event::WriteErr).unwrap();
-                    self.actions.pop_front();
-                    ret
-                }
-                Action::WriteError(_) => Poll::Pending,
-            },
+        let chunk = match ready!(self.poll_read_front(cx))? {
+            Some(chunk) => chunk,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        if buf.remaining() < chunk && self.strict_reads {
+            panic!(
+                "Expecting a read for at least {} bytes but only space for {} bytes{}",
+                chunk,
+                buf.remaining(),
+                chunking_note(self.chunker.chunking())
+            );
         }
+
+        let count = chunk.min(buf.remaining());
+        if let Some(Action::Read(bytes)) = self.actions.front() {
+            buf.put_slice(&bytes[..count]);
+        }
+        self.consume_read(count);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl tokio::io::AsyncBufRead for Mock {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        let chunk = match ready!(this.poll_read_front(cx))? {
+            Some(chunk) => chunk,
+            None => return Poll::Ready(Ok(&[])),
+        };
+
+        if chunk == 0 {
+            // an empty queued read looks like end-of-stream, just as it does for poll_read
+            this.consume_read(0);
+            return Poll::Ready(Ok(&[]));
+        }
+
+        match this.actions.front() {
+            Some(Action::Read(bytes)) => Poll::Ready(Ok(&bytes[..chunk])),
+            _ => unreachable!("poll_read_front() returned a chunk without a read"),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_read(amt);
     }
 }
 
@@ -216,25 +1217,118 @@ impl tokio::io::AsyncWrite for Mock {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<Result<usize, Error>> {
-        match self.front(cx) {
-            Some(Action::WriteError(kind)) => {
-                let kind = *kind;
-                self.tx.send(Event::WriteErr).unwrap();
-                self.actions.pop_front();
-                Poll::Ready(Err(kind.into()))
+        self.write_data(cx, buf, None)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<Result<usize, Error>> {
+        if !self.write_vectored {
+            // behave like the default implementation and write the first non-empty buffer
+            let buf = bufs
+                .iter()
+                .find(|x| !x.is_empty())
+                .map_or(&[][..], |x| &**x);
+            return self.write_data(cx, buf, None);
+        }
+
+        let data: Vec<u8> = bufs.iter().flat_map(|x| x.iter()).copied().collect();
+        self.write_data(cx, &data, Some(bufs))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.write_vectored
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if self.shared.lock().unwrap().flush.wait(cx) {
+            return Poll::Pending;
+        }
+
+        ready!(self.poll_optional_actions(cx));
+        if self.disconnected {
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        match self.actions.front() {
+            Some(Action::FlushError(err)) => {
+                let kind = err.kind();
+                self.event(Event::FlushErr(kind));
+                Poll::Ready(Err(self.pop_error()))
             }
             _ => {
-                self.tx.send(Event::Write(buf.to_vec())).unwrap();
-                Poll::Ready(Ok(buf.len()))
+                self.event(Event::Flush);
+                Poll::Ready(Ok(()))
             }
         }
     }
 
-    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if self.shared.lock().unwrap().shutdown.wait(cx) {
+            return Poll::Pending;
+        }
+
+        ready!(self.poll_optional_actions(cx));
+        if self.disconnected {
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        match self.actions.front() {
+            Some(Action::ShutdownError(err)) => {
+                let kind = err.kind();
+                self.event(Event::ShutdownErr(kind));
+                Poll::Ready(Err(self.pop_error()))
+            }
+            _ => {
+                self.event(Event::Shutdown);
+                Poll::Ready(Ok(()))
+            }
+        }
     }
+}
 
-    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
-        Poll::Ready(Ok(()))
+impl tokio::io::AsyncSeek for Mock {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        self.seek = Some(position);
+        Ok(())
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let target = match self.seek {
+            Some(target) => target,
+            None => return Poll::Ready(Ok(self.position)),
+        };
+
+        ready!(self.poll_actions(cx));
+        if self.disconnected {
+            self.seek = None;
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+        if self.script_complete() {
+            panic!("Unexpected seek to {:?}, the script is complete", target);
+        }
+
+        match ready!(self.front(cx)) {
+            Some(Action::Seek(position)) => {
+                let position = *position;
+                self.seek = None;
+                self.position = position;
+                self.event(Event::Seek(target));
+                self.pop_front();
+                Poll::Ready(Ok(position))
+            }
+            Some(Action::SeekError(err)) => {
+                let kind = err.kind();
+                self.seek = None;
+                self.event(Event::SeekErr(kind));
+                Poll::Ready(Err(self.pop_error()))
+            }
+            _ => {
+                self.seek_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
     }
 }
@@ -0,0 +1,149 @@
+use crate::hex;
+
+/// Predicate used by [`Matcher::Custom`]
+pub type Predicate = Box<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Describes the bytes expected from a write when they can't be predicted exactly
+///
+/// Queue with [`Handle::write_matching`](crate::Handle::write_matching) or
+/// [`Builder::write_matching`](crate::Builder::write_matching). A write that doesn't match panics
+/// just like a mismatched exact write.
+pub enum Matcher {
+    /// the write must match these bytes exactly
+    Exact(Vec<u8>),
+    /// the write must have the same length, with `None` matching any byte
+    Mask(Vec<Option<u8>>),
+    /// the write must be exactly this many bytes long
+    Length(usize),
+    /// the write must start with these bytes
+    Prefix(Vec<u8>),
+    /// the write must end with these bytes
+    Suffix(Vec<u8>),
+    /// the write must satisfy this predicate
+    Custom(Predicate),
+}
+
+impl Matcher {
+    /// Create a matcher from a predicate
+    pub fn custom<F>(predicate: F) -> Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        Self::Custom(Box::new(predicate))
+    }
+
+    /// Returns the offset of the first mismatched byte, or `None` if the write matches
+    pub(crate) fn mismatch(&self, actual: &[u8]) -> Option<usize> {
+        match self {
+            Matcher::Exact(expected) => first_difference(expected, actual, |x, y| *x == y),
+            Matcher::Mask(expected) => first_difference(expected, actual, |x, y| match x {
+                Some(x) => *x == y,
+                None => true,
+            }),
+            Matcher::Length(len) => {
+                if actual.len() == *len {
+                    None
+                } else {
+                    Some(actual.len().min(*len))
+                }
+            }
+            Matcher::Prefix(prefix) => {
+                let len = prefix.len().min(actual.len());
+                first_difference(prefix, &actual[..len], |x, y| *x == y)
+            }
+            Matcher::Suffix(suffix) => {
+                if actual.len() < suffix.len() {
+                    return Some(0);
+                }
+                let start = actual.len() - suffix.len();
+                first_difference(suffix, &actual[start..], |x, y| *x == y).map(|x| x + start)
+            }
+            Matcher::Custom(predicate) => {
+                if predicate(actual) {
+                    None
+                } else {
+                    Some(0)
+                }
+            }
+        }
+    }
+
+    /// True if a write cut short after `actual` has yet to supply every byte the matcher expects
+    ///
+    /// Suffix and custom matchers can't tell, so they wait for the caller's retry to complete
+    pub(crate) fn needs_more(&self, actual: &[u8]) -> bool {
+        match self {
+            Matcher::Exact(expected) => actual.len() < expected.len(),
+            Matcher::Mask(expected) => actual.len() < expected.len(),
+            Matcher::Length(len) => actual.len() < *len,
+            Matcher::Prefix(prefix) => actual.len() < prefix.len(),
+            Matcher::Suffix(_) | Matcher::Custom(_) => true,
+        }
+    }
+
+    /// Returns the offset of the first mismatched byte among the start of a write cut short
+    pub(crate) fn prefix_mismatch(&self, actual: &[u8]) -> Option<usize> {
+        let len = actual.len();
+        match self {
+            Matcher::Exact(expected) | Matcher::Prefix(expected) => {
+                let len = len.min(expected.len());
+                first_difference(&expected[..len], &actual[..len], |x, y| *x == y)
+            }
+            Matcher::Mask(expected) => {
+                let len = len.min(expected.len());
+                Matcher::Mask(expected[..len].to_vec()).mismatch(&actual[..len])
+            }
+            Matcher::Length(_) | Matcher::Suffix(_) | Matcher::Custom(_) => None,
+        }
+    }
+}
+
+fn first_difference<T>(
+    expected: &[T],
+    actual: &[u8],
+    matches: impl Fn(&T, u8) -> bool,
+) -> Option<usize> {
+    match expected
+        .iter()
+        .zip(actual.iter())
+        .position(|(x, y)| !matches(x, *y))
+    {
+        Some(position) => Some(position),
+        None if expected.len() != actual.len() => Some(expected.len().min(actual.len())),
+        None => None,
+    }
+}
+
+impl std::fmt::Display for Matcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Matcher::Exact(bytes) => write!(f, "({} bytes): [{}]", bytes.len(), hex(bytes)),
+            Matcher::Mask(mask) => write!(
+                f,
+                "({} bytes): [{}]",
+                mask.len(),
+                mask.iter()
+                    .map(|x| match x {
+                        Some(x) => format!("{:02X}", x),
+                        None => "??".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Matcher::Length(len) => write!(f, "({} bytes): any", len),
+            Matcher::Prefix(bytes) => {
+                write!(f, "prefix ({} bytes): [{}]", bytes.len(), hex(bytes))
+            }
+            Matcher::Suffix(bytes) => {
+                write!(f, "suffix ({} bytes): [{}]", bytes.len(), hex(bytes))
+            }
+            Matcher::Custom(_) => f.write_str("custom predicate"),
+        }
+    }
+}
+
+impl std::fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Matcher {}", self)
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use tokio::io::ReadBuf;
+
+use crate::Event;
+
+/// Create two connected endpoints and a Tap that observes and manipulates the traffic between them
+///
+/// Bytes written to one endpoint become readable on the other. Every operation is reported to the
+/// Tap as an [`Event`] tagged with the [`Side`] that performed it.
+pub fn pair() -> (Endpoint, Endpoint, Tap) {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let link = Arc::new(Mutex::new(Link::default()));
+    let a = Endpoint {
+        side: Side::A,
+        link: link.clone(),
+        tx: tx.clone(),
+    };
+    let b = Endpoint {
+        side: Side::B,
+        link: link.clone(),
+        tx,
+    };
+    let tap = Tap { link, rx };
+    (a, b, tap)
+}
+
+/// Identifies one of the two endpoints created by [`pair`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// first endpoint returned by [`pair`]
+    A,
+    /// second endpoint returned by [`pair`]
+    B,
+}
+
+impl Side {
+    fn index(self) -> usize {
+        match self {
+            Side::A => 0,
+            Side::B => 1,
+        }
+    }
+
+    fn peer(self) -> Self {
+        match self {
+            Side::A => Side::B,
+            Side::B => Side::A,
+        }
+    }
+}
+
+/// One end of a connected pair that can be used in lieu of a socket
+///
+/// Shutting down closes the write side only: the peer reads end-of-stream after any remaining
+/// bytes but can still write, while later writes on this endpoint fail with `BrokenPipe`. Dropping
+/// an endpoint closes both directions, so the peer's writes fail with `BrokenPipe` as well.
+pub struct Endpoint {
+    side: Side,
+    link: Arc<Mutex<Link>>,
+    tx: tokio::sync::mpsc::UnboundedSender<(Side, Event)>,
+}
+
+/// Sits between the two endpoints of a pair, observing events and manipulating the traffic
+pub struct Tap {
+    link: Arc<Mutex<Link>>,
+    rx: tokio::sync::mpsc::UnboundedReceiver<(Side, Event)>,
+}
+
+#[derive(Default)]
+struct Link {
+    ports: [Port; 2],
+}
+
+/// State of the traffic flowing into and out of one endpoint
+#[derive(Default)]
+struct Port {
+    // bytes written by the peer that this endpoint can read
+    incoming: VecDeque<u8>,
+    // bytes written by this endpoint that the tap is holding back
+    held: Option<Vec<u8>>,
+    // number of bytes written by this endpoint that the tap will discard
+    drop: usize,
+    // the peer shut down or was dropped, so reads end once the incoming bytes are consumed
+    peer_closed: bool,
+    // the peer was dropped, so nobody reads what this endpoint writes
+    peer_dropped: bool,
+    // this endpoint shut down its write side
+    write_closed: bool,
+    // errors returned by the next reads on this endpoint
+    read_errors: VecDeque<Error>,
+    // errors returned by the next writes on this endpoint
+    write_errors: VecDeque<Error>,
+    // reader waiting for incoming bytes
+    read_waker: Option<Waker>,
+}
+
+impl Port {
+    fn wake(&mut self) {
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Link {
+    fn port(&mut self, side: Side) -> &mut Port {
+        &mut self.ports[side.index()]
+    }
+
+    fn deliver(&mut self, to: Side, data: &[u8]) {
+        let port = self.port(to);
+        port.incoming.extend(data);
+        port.wake();
+    }
+
+    fn shutdown(&mut self, side: Side) {
+        self.port(side).write_closed = true;
+        let peer = self.port(side.peer());
+        peer.peer_closed = true;
+        peer.wake();
+    }
+
+    fn close(&mut self, side: Side) {
+        self.shutdown(side);
+        self.port(side.peer()).peer_dropped = true;
+    }
+}
+
+impl Tap {
+    /// Make the next read on `side` fail with an error of the specified kind
+    pub fn read_error(&mut self, side: Side, kind: ErrorKind) {
+        self.read_error_with(side, kind.into())
+    }
+
+    /// Make the next read on `side` fail with `err` verbatim
+    pub fn read_error_with(&mut self, side: Side, err: Error) {
+        let mut link = self.link.lock().unwrap();
+        let port = link.port(side);
+        port.read_errors.push_back(err);
+        port.wake();
+    }
+
+    /// Make the next write on `side` fail with an error of the specified kind
+    pub fn write_error(&mut self, side: Side, kind: ErrorKind) {
+        self.write_error_with(side, kind.into())
+    }
+
+    /// Make the next write on `side` fail with `err` verbatim
+    pub fn write_error_with(&mut self, side: Side, err: Error) {
+        self.link
+            .lock()
+            .unwrap()
+            .port(side)
+            .write_errors
+            .push_back(err);
+    }
+
+    /// Discard the next `count` bytes written by `side` instead of delivering them to its peer
+    ///
+    /// Discarded bytes are still reported in [`Event::Write`]
+    pub fn drop_bytes(&mut self, side: Side, count: usize) {
+        self.link.lock().unwrap().port(side).drop += count;
+    }
+
+    /// Hold back bytes written by `side` until `release` is called
+    pub fn hold(&mut self, side: Side) {
+        let mut link = self.link.lock().unwrap();
+        let port = link.port(side);
+        if port.held.is_none() {
+            port.held = Some(Vec::new());
+        }
+    }
+
+    /// Deliver any bytes held back from `side` and stop holding its writes
+    pub fn release(&mut self, side: Side) {
+        let mut link = self.link.lock().unwrap();
+        if let Some(data) = link.port(side).held.take() {
+            link.deliver(side.peer(), &data);
+        }
+    }
+
+    /// Asynchronously wait for the next event
+    pub async fn next_event(&mut self) -> (Side, Event) {
+        self.rx.recv().await.unwrap()
+    }
+
+    /// Pop the next event if present
+    pub fn pop_event(&mut self) -> Option<(Side, Event)> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl Endpoint {
+    fn event(&self, event: Event) {
+        // the tap may have been dropped, in which case nobody is observing
+        let _ = self.tx.send((self.side, event));
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        if let Ok(mut link) = self.link.lock() {
+            link.close(self.side);
+        }
+    }
+}
+
+impl tokio::io::AsyncRead for Endpoint {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut ReadBuf,
+    ) -> Poll<std::io::Result<()>> {
+        let mut link = self.link.lock().unwrap();
+        let peer_holding = link
+            .port(self.side.peer())
+            .held
+            .as_ref()
+            .is_some_and(|x| !x.is_empty());
+        let port = link.port(self.side);
+
+        if let Some(err) = port.read_errors.pop_front() {
+            self.event(Event::ReadErr(err.kind()));
+            return Poll::Ready(Err(err));
+        }
+
+        if !port.incoming.is_empty() {
+            let count = buf.remaining().min(port.incoming.len());
+            let (first, second) = port.incoming.as_slices();
+            if count <= first.len() {
+                buf.put_slice(&first[..count]);
+            } else {
+                buf.put_slice(first);
+                buf.put_slice(&second[..count - first.len()]);
+            }
+            port.incoming.drain(..count);
+            self.event(Event::Read);
+            return Poll::Ready(Ok(()));
+        }
+
+        if port.peer_closed && !peer_holding {
+            self.event(Event::Eof);
+            return Poll::Ready(Ok(()));
+        }
+
+        port.read_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl tokio::io::AsyncWrite for Endpoint {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Error>> {
+        let mut link = self.link.lock().unwrap();
+        let port = link.port(self.side);
+
+        if let Some(err) = port.write_errors.pop_front() {
+            self.event(Event::WriteErr(err.kind()));
+            return Poll::Ready(Err(err));
+        }
+
+        if port.write_closed || port.peer_dropped {
+            // like a socket after shutdown or whose peer has gone away
+            self.event(Event::WriteErr(ErrorKind::BrokenPipe));
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        self.event(Event::Write(buf.to_vec()));
+
+        let skip = port.drop.min(buf.len());
+        port.drop -= skip;
+        let data = &buf[skip..];
+
+        match port.held.as_mut() {
+            Some(held) => held.extend_from_slice(data),
+            None => link.deliver(self.side.peer(), data),
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.event(Event::Flush);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.link.lock().unwrap().shutdown(self.side);
+        self.event(Event::Shutdown);
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use sfio_tokio_mock_io::{mock, Event};
+use tokio::io::AsyncWriteExt;
+
+#[tokio::test]
+async fn write_limit_accepts_part_of_a_write() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(3);
+    assert_eq!(mock.write(b"hello").await.unwrap(), 3);
+    assert_eq!(mock.write(b"lo").await.unwrap(), 2);
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"hel".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"lo".to_vec())));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn write_limit_with_expected_write() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(3);
+    handle.write(b"hello");
+    handle.write(b"!");
+    mock.write_all(b"hello").await.unwrap();
+    mock.write_all(b"!").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"hel".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"lo".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"!".to_vec())));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 1")]
+async fn short_write_is_still_checked() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write(b"hello");
+    let _ = mock.write(b"hallo").await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 0")]
+async fn rest_of_short_write_is_still_checked() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write(b"hello");
+    assert_eq!(mock.write(b"hello").await.unwrap(), 2);
+    let _ = mock.write(b"xyz").await;
+}
+
+#[tokio::test]
+async fn write_buffer_with_expected_write() {
+    let (mock, mut handle) = mock();
+    let (_, mut writer) = tokio::io::split(mock);
+    handle.set_write_buffer(Some(2));
+    handle.write(b"hello");
+    let task = tokio::spawn(async move {
+        writer.write_all(b"hello").await.unwrap();
+    });
+
+    assert_eq!(handle.next_event().await, Event::Write(b"he".to_vec()));
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    handle.drain_write_buffer(2);
+    assert_eq!(handle.next_event().await, Event::Write(b"ll".to_vec()));
+    handle.drain_write_buffer(1);
+    assert_eq!(handle.next_event().await, Event::Write(b"o".to_vec()));
+    task.await.unwrap();
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn held_write_is_pending_until_released() {
+    let (mock, mut handle) = mock();
+    let (_, mut writer) = tokio::io::split(mock);
+    handle.hold_write();
+    handle.write(b"abc");
+    let task = tokio::spawn(async move { writer.write(b"abc").await.unwrap() });
+
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    assert_eq!(handle.pop_event(), None);
+    handle.release_write();
+    assert_eq!(task.await.unwrap(), 3);
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"abc".to_vec())));
+}
+
+#[tokio::test]
+async fn held_flush_is_pending_until_released() {
+    let (mock, mut handle) = mock();
+    let (_, mut writer) = tokio::io::split(mock);
+    handle.hold_flush();
+    let task = tokio::spawn(async move { writer.flush().await.unwrap() });
+
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    assert_eq!(handle.pop_event(), None);
+    handle.release_flush();
+    task.await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Flush));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn held_shutdown_is_pending_until_released() {
+    let (mock, mut handle) = mock();
+    let (_, mut writer) = tokio::io::split(mock);
+    handle.hold_shutdown();
+    let task = tokio::spawn(async move { writer.shutdown().await.unwrap() });
+
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    assert_eq!(handle.pop_event(), None);
+    handle.release_shutdown();
+    task.await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Shutdown));
+    handle.assert_idle();
+}
+
+#[tokio::test(start_paused = true)]
+async fn held_flush_times_out_and_can_be_retried() {
+    let (mut mock, mut handle) = mock();
+    handle.hold_flush();
+    let result = tokio::time::timeout(Duration::from_secs(1), mock.flush()).await;
+    assert!(result.is_err());
+    assert_eq!(handle.pop_event(), None);
+
+    handle.release_flush();
+    mock.flush().await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Flush));
+    handle.assert_idle();
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_shutdown_consumes_nothing() {
+    let (mut mock, mut handle) = mock();
+    handle.hold_shutdown();
+    handle.shutdown_error(ErrorKind::BrokenPipe);
+    let result = tokio::time::timeout(Duration::from_secs(1), mock.shutdown()).await;
+    assert!(result.is_err());
+    assert_eq!(handle.pop_event(), None);
+
+    handle.release_shutdown();
+    let err = mock.shutdown().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::ShutdownErr(ErrorKind::BrokenPipe))
+    );
+    handle.assert_idle();
+}
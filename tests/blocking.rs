@@ -0,0 +1,68 @@
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+
+use sfio_tokio_mock_io::{blocking_mock, BlockingMock, Builder, Event};
+
+#[test]
+fn reads_and_writes_without_a_runtime() {
+    let (mut mock, mut handle) = blocking_mock();
+    handle.read(b"abc");
+    handle.write(b"xy");
+    let mut buf = [0; 8];
+    assert_eq!(mock.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"abc");
+    mock.write_all(b"xy").unwrap();
+    mock.flush().unwrap();
+    assert_eq!(handle.blocking_next_event(), Event::Read);
+    assert_eq!(handle.blocking_next_event(), Event::Write(b"xy".to_vec()));
+    assert_eq!(handle.blocking_next_event(), Event::Flush);
+}
+
+#[test]
+fn read_blocks_until_handle_queues_data() {
+    let (mut mock, mut handle) = blocking_mock();
+    let reader = std::thread::spawn(move || {
+        let mut buf = [0; 8];
+        mock.read(&mut buf).unwrap()
+    });
+    std::thread::sleep(Duration::from_millis(10));
+    handle.read(b"late");
+    assert_eq!(reader.join().unwrap(), 4);
+    assert_eq!(handle.blocking_next_event(), Event::Read);
+}
+
+#[test]
+fn read_times_out() {
+    let (mut mock, mut handle) = blocking_mock();
+    mock.set_timeout(Some(Duration::from_millis(10)));
+    let mut buf = [0; 8];
+    let err = mock.read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+    handle.read(b"ok");
+    assert_eq!(mock.read(&mut buf).unwrap(), 2);
+}
+
+#[test]
+fn scripted_errors() {
+    let (mut mock, mut handle) = blocking_mock();
+    handle.read_error(ErrorKind::ConnectionReset);
+    handle.write_error(ErrorKind::BrokenPipe);
+    handle.flush_error(ErrorKind::Interrupted);
+    let mut buf = [0; 8];
+    assert_eq!(
+        mock.read(&mut buf).unwrap_err().kind(),
+        ErrorKind::ConnectionReset
+    );
+    assert_eq!(mock.write(b"x").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    assert_eq!(mock.flush().unwrap_err().kind(), ErrorKind::Interrupted);
+}
+
+#[test]
+fn builder_script_ends_with_eof() {
+    let mut mock = BlockingMock::new(Builder::new().read(b"hello").build());
+    let mut data = Vec::new();
+    mock.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"hello");
+    mock.into_inner().verify().unwrap();
+}
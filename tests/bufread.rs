@@ -0,0 +1,49 @@
+use sfio_tokio_mock_io::{Builder, Chunking, Event};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+#[tokio::test]
+async fn lines_span_queued_reads() {
+    let (mut mock, mut handle) = Builder::new()
+        .read(b"line one\nline")
+        .read(b" two\n")
+        .read_eof()
+        .build_with_handle();
+    let mut line = String::new();
+    mock.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "line one\n");
+    assert_eq!(handle.pop_event(), None);
+
+    line.clear();
+    mock.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "line two\n");
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+
+    line.clear();
+    assert_eq!(mock.read_line(&mut line).await.unwrap(), 0);
+    assert_eq!(handle.pop_event(), Some(Event::Eof));
+}
+
+#[tokio::test]
+async fn fill_buf_honours_chunking() {
+    let mut mock = Builder::new()
+        .read(b"abcde")
+        .read_chunking(Chunking::Fixed(2))
+        .build();
+    assert_eq!(mock.fill_buf().await.unwrap(), b"ab");
+    mock.consume(1);
+    let mut rest = Vec::new();
+    mock.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, b"bcde");
+}
+
+#[tokio::test]
+async fn mixes_with_plain_reads() {
+    let mut mock = Builder::new().read(b"head\nbody").build();
+    let mut line = String::new();
+    mock.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "head\n");
+    let mut body = [0; 4];
+    mock.read_exact(&mut body).await.unwrap();
+    assert_eq!(&body, b"body");
+}
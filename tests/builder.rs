@@ -0,0 +1,65 @@
+use std::io::{ErrorKind, SeekFrom};
+
+use sfio_tokio_mock_io::{Builder, Event};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn runs_script_without_handle() {
+    let mut mock = Builder::new()
+        .read(b"hello")
+        .write(b"world")
+        .read_error(ErrorKind::ConnectionReset)
+        .build();
+    let mut buf = [0; 5];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+    mock.write_all(b"world").await.unwrap();
+    let err = mock.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+    mock.verify().unwrap();
+}
+
+#[tokio::test]
+async fn read_after_script_returns_eof() {
+    let mut mock = Builder::new().read(b"abc").build();
+    let mut data = Vec::new();
+    mock.read_to_end(&mut data).await.unwrap();
+    assert_eq!(data, b"abc");
+    let mut buf = [0; 4];
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 0);
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 0\nexpected nothing, the script is complete")]
+async fn write_after_script_panics() {
+    let mut mock = Builder::new().write(b"abc").build();
+    mock.write_all(b"abc").await.unwrap();
+    let _ = mock.write(b"d").await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected seek to Start(0), the script is complete")]
+async fn seek_after_script_panics() {
+    let mut mock = Builder::new().build();
+    let _ = mock.seek(SeekFrom::Start(0)).await;
+}
+
+#[tokio::test]
+async fn handle_extends_script() {
+    let (mut mock, mut handle) = Builder::new().read(b"ab").build_with_handle();
+    handle.write(b"cd");
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+    mock.write_all(b"cd").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"cd".to_vec())));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+#[should_panic(expected = "1 unused mock action(s):\n  1: write (2 bytes): [63 64]")]
+async fn unused_script_panics_on_drop() {
+    let mut mock = Builder::new().read(b"ab").write(b"cd").build();
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+}
@@ -0,0 +1,116 @@
+use sfio_tokio_mock_io::{Builder, Chunking, Mock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Sizes of the reads performed until `len` bytes have been received
+async fn read_sizes(mock: &mut Mock, len: usize) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    let mut buf = [0; 64];
+    let mut total = 0;
+    while total < len {
+        let count = mock.read(&mut buf).await.unwrap();
+        assert_ne!(count, 0);
+        sizes.push(count);
+        total += count;
+    }
+    sizes
+}
+
+#[test]
+fn exhaustive_enumerates_every_split() {
+    let all: Vec<Chunking> = Chunking::exhaustive(3).collect();
+    assert_eq!(
+        all,
+        vec![
+            Chunking::Sizes(vec![3]),
+            Chunking::Sizes(vec![1, 2]),
+            Chunking::Sizes(vec![2, 1]),
+            Chunking::Sizes(vec![1, 1, 1]),
+        ]
+    );
+    assert_eq!(Chunking::exhaustive(0).count(), 1);
+    assert_eq!(Chunking::exhaustive(1).count(), 1);
+}
+
+#[test]
+fn exhaustive_accepts_64_bytes() {
+    let first = Chunking::exhaustive(64).next();
+    assert_eq!(first, Some(Chunking::Sizes(vec![64])));
+}
+
+#[test]
+#[should_panic(expected = "cannot enumerate the splits of a 70 byte read")]
+fn exhaustive_rejects_long_reads() {
+    let _ = Chunking::exhaustive(70);
+}
+
+#[tokio::test]
+async fn every_chunking_delivers_the_same_bytes() {
+    let chunkings = Chunking::exhaustive(5)
+        .chain((0..32).map(Chunking::Random))
+        .chain([Chunking::Fixed(2), Chunking::Whole]);
+    for chunking in chunkings {
+        let mut mock = Builder::new()
+            .read(b"abcde")
+            .read(b"fg")
+            .read_chunking(chunking.clone())
+            .build();
+        let mut data = vec![0; 7];
+        mock.read_exact(&mut data).await.unwrap();
+        assert_eq!(data, b"abcdefg", "{:?}", chunking);
+    }
+}
+
+#[tokio::test]
+async fn fixed_and_sized_chunks() {
+    let mut mock = Builder::new()
+        .read(b"abcdefg")
+        .read_chunking(Chunking::Fixed(3))
+        .build();
+    assert_eq!(read_sizes(&mut mock, 7).await, vec![3, 3, 1]);
+
+    let mut mock = Builder::new()
+        .read(b"abcdefg")
+        .read(b"hi")
+        .read_chunking(Chunking::Sizes(vec![1, 4]))
+        .build();
+    assert_eq!(read_sizes(&mut mock, 9).await, vec![1, 4, 1, 1, 1, 1]);
+}
+
+#[tokio::test]
+async fn random_chunking_replays_from_seed() {
+    let mut sizes = Vec::new();
+    for _ in 0..2 {
+        let mut mock = Builder::new()
+            .read(&[0; 32])
+            .read_chunking(Chunking::Random(1234))
+            .build();
+        sizes.push(read_sizes(&mut mock, 32).await);
+    }
+    assert_eq!(sizes[0], sizes[1]);
+    assert!(sizes[0].len() > 1);
+}
+
+#[tokio::test]
+#[should_panic(expected = "actual (1 bytes): [78]\nread chunking: Random(42)")]
+async fn unexpected_write_reports_chunking() {
+    let mut mock = Builder::new()
+        .read(b"abc")
+        .write(b"y")
+        .read_chunking(Chunking::Random(42))
+        .build();
+    let mut data = [0; 3];
+    mock.read_exact(&mut data).await.unwrap();
+    let _ = mock.write_all(b"x").await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "only space for 1 bytes\nread chunking: Fixed(2)")]
+async fn strict_read_reports_chunking() {
+    let mut mock = Builder::new()
+        .read(b"abc")
+        .read_chunking(Chunking::Fixed(2))
+        .strict_reads(true)
+        .build();
+    let mut buf = [0; 1];
+    let _ = mock.read(&mut buf).await;
+}
@@ -0,0 +1,85 @@
+use sfio_tokio_mock_io::{Builder, Event, Matcher};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn writes_may_split_and_merge_expectations() {
+    let (mut mock, mut handle) = Builder::new()
+        .coalesce_writes(true)
+        .write(b"abc")
+        .write(b"de")
+        .read(b"x")
+        .build_with_handle();
+    mock.write_all(b"a").await.unwrap();
+    mock.write_all(b"bcd").await.unwrap();
+    mock.write_all(b"e").await.unwrap();
+    let mut buf = [0; 1];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"a".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"bcd".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"e".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn coalescing_stops_at_other_actions() {
+    let (mut mock, mut handle) = Builder::new()
+        .coalesce_writes(true)
+        .write(b"ab")
+        .read(b"r")
+        .write(b"cd")
+        .build_with_handle();
+    mock.write_all(b"a").await.unwrap();
+    mock.write_all(b"b").await.unwrap();
+    let mut buf = [0; 1];
+    mock.read_exact(&mut buf).await.unwrap();
+    mock.write_all(b"cd").await.unwrap();
+    mock.verify().unwrap();
+    assert_eq!(handle.verify().unwrap_err().events.len(), 4);
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 0\nexpected (1 bytes): [63]")]
+async fn mismatch_reports_remaining_expectation() {
+    let mut mock = Builder::new().coalesce_writes(true).write(b"abc").build();
+    mock.write_all(b"ab").await.unwrap();
+    let _ = mock.write(b"x").await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 2")]
+async fn without_coalescing_each_write_must_match() {
+    let mut mock = Builder::new().write(b"abc").build();
+    let _ = mock.write(b"ab").await;
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "Unexpected write at byte 0\nexpected nothing, the script is complete\n  actual (3 bytes): [64 65 66]"
+)]
+async fn trailing_bytes_after_script_panic() {
+    let mut mock = Builder::new().coalesce_writes(true).write(b"abc").build();
+    let _ = mock.write_all(b"abcdef").await;
+}
+
+#[tokio::test]
+async fn trailing_bytes_are_checked_by_next_matcher() {
+    let mut mock = Builder::new()
+        .coalesce_writes(true)
+        .write(b"abc")
+        .write_matching(Matcher::Length(2))
+        .build();
+    mock.write_all(b"abcde").await.unwrap();
+    mock.verify().unwrap();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 0\nexpected prefix (1 bytes): [78]")]
+async fn trailing_bytes_must_match_next_matcher() {
+    let mut mock = Builder::new()
+        .coalesce_writes(true)
+        .write(b"abc")
+        .write_matching(Matcher::Prefix(b"x".to_vec()))
+        .build();
+    let _ = mock.write_all(b"abcde").await;
+}
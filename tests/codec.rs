@@ -0,0 +1,57 @@
+#![cfg(feature = "codec")]
+
+use futures::{SinkExt, StreamExt};
+use sfio_tokio_mock_io::{framed, Event, FrameEvent};
+use tokio_util::codec::LinesCodec;
+
+#[tokio::test]
+async fn frames_are_read_and_written() {
+    let (mut framed, mut handle) = framed(LinesCodec::new(), LinesCodec::new());
+    handle.read_frame("hello").unwrap();
+    assert_eq!(framed.next().await.unwrap().unwrap(), "hello");
+
+    framed.send("world").await.unwrap();
+    assert_eq!(
+        handle.next_frame().await.unwrap(),
+        FrameEvent::Event(Event::Read)
+    );
+    assert_eq!(
+        handle.next_frame().await.unwrap(),
+        FrameEvent::Frame("world".to_string())
+    );
+    assert_eq!(
+        handle.next_frame().await.unwrap(),
+        FrameEvent::Event(Event::Flush)
+    );
+}
+
+#[tokio::test]
+async fn expected_frames_are_checked() {
+    let (mut framed, mut handle) = framed(LinesCodec::new(), LinesCodec::new());
+    handle.write_frame("ping").unwrap();
+    framed.send("ping").await.unwrap();
+    assert_eq!(
+        handle.next_frame().await.unwrap(),
+        FrameEvent::Frame("ping".to_string())
+    );
+}
+
+#[tokio::test]
+async fn frames_split_across_writes_are_reassembled() {
+    let (mut framed, mut handle) = framed(LinesCodec::new(), LinesCodec::new());
+    handle.handle().write_limit(2);
+    framed.send("abcd").await.unwrap();
+    assert_eq!(
+        handle.next_frame().await.unwrap(),
+        FrameEvent::Frame("abcd".to_string())
+    );
+}
+
+#[tokio::test]
+async fn byte_level_actions_between_frames() {
+    let (mut framed, mut handle) = framed(LinesCodec::new(), LinesCodec::new());
+    handle.read_frame("one").unwrap();
+    handle.handle().read_eof();
+    assert_eq!(framed.next().await.unwrap().unwrap(), "one");
+    assert!(framed.next().await.is_none());
+}
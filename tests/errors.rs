@@ -0,0 +1,79 @@
+use std::io::{Error, ErrorKind, SeekFrom};
+
+use sfio_tokio_mock_io::{mock, Builder, Event};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+#[derive(Debug)]
+struct Custom;
+
+impl std::fmt::Display for Custom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("custom inner error")
+    }
+}
+
+impl std::error::Error for Custom {}
+
+#[tokio::test]
+async fn errors_are_returned_verbatim() {
+    let (mut mock, mut handle) = mock();
+    handle.read_error_with(Error::new(ErrorKind::InvalidData, "bad frame"));
+    handle.write_error_with(Error::from_raw_os_error(104));
+    handle.flush_error_with(Error::other(Custom));
+    handle.shutdown_error_with(Error::new(ErrorKind::TimedOut, "linger"));
+    handle.seek_error_with(Error::new(ErrorKind::Unsupported, "pipe"));
+
+    let mut buf = [0; 4];
+    let err = mock.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "bad frame");
+    let err = mock.write(b"x").await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(104));
+    let err = mock.flush().await.unwrap_err();
+    assert!(err.get_ref().unwrap().is::<Custom>());
+    let err = mock.shutdown().await.unwrap_err();
+    assert_eq!(err.to_string(), "linger");
+    let err = mock.seek(SeekFrom::Start(0)).await.unwrap_err();
+    assert_eq!(err.to_string(), "pipe");
+
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::ReadErr(ErrorKind::InvalidData))
+    );
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::WriteErr(Error::from_raw_os_error(104).kind()))
+    );
+    assert_eq!(handle.pop_event(), Some(Event::FlushErr(ErrorKind::Other)));
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::ShutdownErr(ErrorKind::TimedOut))
+    );
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::SeekErr(ErrorKind::Unsupported))
+    );
+}
+
+#[tokio::test]
+async fn builder_errors_are_returned_verbatim() {
+    let mut mock = Builder::new()
+        .read_error_with(Error::new(ErrorKind::InvalidData, "bad frame"))
+        .write_error_with(Error::new(ErrorKind::WriteZero, "full"))
+        .build();
+    let mut buf = [0; 4];
+    assert_eq!(
+        mock.read(&mut buf).await.unwrap_err().to_string(),
+        "bad frame"
+    );
+    assert_eq!(mock.write(b"x").await.unwrap_err().to_string(), "full");
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "1 unused mock action(s):\n  1: read error: Custom { kind: InvalidData, error: \"bad frame\" }"
+)]
+async fn unused_errors_are_described() {
+    let (_mock, mut handle) = mock();
+    handle.read_error_with(Error::new(ErrorKind::InvalidData, "bad frame"));
+}
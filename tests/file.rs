@@ -0,0 +1,159 @@
+use std::future::poll_fn;
+use std::io::{ErrorKind, SeekFrom};
+use std::pin::Pin;
+use std::task::Poll;
+
+use sfio_tokio_mock_io::{mock, mock_file, Event};
+use tokio::io::{AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn scripted_seek_results() {
+    let (mut mock, mut handle) = mock();
+    handle.seek(10);
+    handle.seek_error(ErrorKind::InvalidInput);
+    assert_eq!(mock.seek(SeekFrom::Current(3)).await.unwrap(), 10);
+    let err = mock.seek(SeekFrom::Start(1)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(handle.pop_event(), Some(Event::Seek(SeekFrom::Current(3))));
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::SeekErr(ErrorKind::InvalidInput))
+    );
+}
+
+#[tokio::test]
+async fn seek_waits_behind_read() {
+    let (mut mock, mut handle) = mock();
+    handle.read(b"ab");
+    handle.seek(7);
+    Pin::new(&mut mock).start_seek(SeekFrom::Start(7)).unwrap();
+    let pending =
+        poll_fn(|cx| Poll::Ready(Pin::new(&mut mock).poll_complete(cx).is_pending())).await;
+    assert!(pending);
+
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+    let position = poll_fn(|cx| Pin::new(&mut mock).poll_complete(cx)).await;
+    assert_eq!(position.unwrap(), 7);
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    assert_eq!(handle.pop_event(), Some(Event::Seek(SeekFrom::Start(7))));
+}
+
+#[tokio::test]
+async fn reads_observe_writes() {
+    let (mut file, mut handle) = mock_file(b"hello");
+    file.seek(SeekFrom::End(0)).await.unwrap();
+    file.write_all(b" world").await.unwrap();
+    file.rewind().await.unwrap();
+    let mut text = String::new();
+    file.read_to_string(&mut text).await.unwrap();
+    assert_eq!(text, "hello world");
+    assert_eq!(file.contents(), b"hello world");
+    assert_eq!(handle.pop_event(), Some(Event::Seek(SeekFrom::End(0))));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b" world".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Seek(SeekFrom::Start(0))));
+}
+
+#[tokio::test]
+async fn write_past_end_fills_gap() {
+    let (mut file, _handle) = mock_file(b"ab");
+    file.seek(SeekFrom::Start(4)).await.unwrap();
+    file.write_all(b"!").await.unwrap();
+    assert_eq!(file.contents(), b"ab\0\0!");
+}
+
+#[tokio::test]
+async fn writes_are_checked() {
+    let (mut file, mut handle) = mock_file(b"");
+    handle.write(b"abc");
+    file.write_all(b"abc").await.unwrap();
+    assert_eq!(file.contents(), b"abc");
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"abc".to_vec())));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn scripted_errors() {
+    let (mut file, mut handle) = mock_file(b"abc");
+    handle.read_error(ErrorKind::Interrupted);
+    handle.seek_error(ErrorKind::PermissionDenied);
+    handle.write_error(ErrorKind::StorageFull);
+    let mut buf = [0; 3];
+    assert_eq!(
+        file.read(&mut buf).await.unwrap_err().kind(),
+        ErrorKind::Interrupted
+    );
+    assert_eq!(
+        file.seek(SeekFrom::Start(1)).await.unwrap_err().kind(),
+        ErrorKind::PermissionDenied
+    );
+    assert_eq!(
+        file.write(b"x").await.unwrap_err().kind(),
+        ErrorKind::StorageFull
+    );
+    assert_eq!(file.contents(), b"abc");
+}
+
+#[tokio::test]
+async fn scripted_seek_overrides_position() {
+    let (mut file, mut handle) = mock_file(b"abcdef");
+    handle.seek(4);
+    assert_eq!(file.seek(SeekFrom::Start(1)).await.unwrap(), 4);
+    let mut buf = [0; 2];
+    file.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ef");
+}
+
+#[tokio::test]
+async fn invalid_seek_fails() {
+    let (mut file, mut handle) = mock_file(b"abc");
+    let err = file.seek(SeekFrom::Current(-4)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    file.seek(SeekFrom::Start(u64::MAX)).await.unwrap();
+    let err = file.seek(SeekFrom::Current(1)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::SeekErr(ErrorKind::InvalidInput))
+    );
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::Seek(SeekFrom::Start(u64::MAX)))
+    );
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::SeekErr(ErrorKind::InvalidInput))
+    );
+}
+
+#[tokio::test]
+async fn write_at_huge_position_fails() {
+    let (mut file, mut handle) = mock_file(b"abc");
+    file.seek(SeekFrom::Start(u64::MAX)).await.unwrap();
+    let err = file.write(b"x").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(
+        err.to_string(),
+        "write beyond the maximum size of the mock file"
+    );
+    assert_eq!(file.contents(), b"abc");
+    let mut buf = [0; 1];
+    assert_eq!(file.read(&mut buf).await.unwrap(), 0);
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::Seek(SeekFrom::Start(u64::MAX)))
+    );
+    assert_eq!(handle.pop_event(), Some(Event::WriteErr(ErrorKind::Other)));
+}
+
+#[tokio::test]
+async fn write_is_cut_short_at_max_size() {
+    let (mut file, _handle) = mock_file(b"ab");
+    file.set_max_size(4);
+    assert_eq!(file.write(b"wxyz").await.unwrap(), 4);
+    let err = file.write(b"!").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    file.seek(SeekFrom::Start(2)).await.unwrap();
+    assert_eq!(file.write(b"xyz").await.unwrap(), 2);
+    assert_eq!(file.contents(), b"wxxy");
+}
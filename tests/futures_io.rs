@@ -0,0 +1,48 @@
+#![cfg(feature = "futures-io")]
+
+use futures::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use sfio_tokio_mock_io::{Builder, Event};
+
+#[tokio::test]
+async fn futures_traits_drive_the_same_script() {
+    let (mut mock, mut handle) = Builder::new()
+        .read(b"abc\n")
+        .read(b"xy")
+        .write(b"out")
+        .read_eof()
+        .build_with_handle();
+    let mut line = String::new();
+    mock.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "abc\n");
+    let mut buf = [0; 8];
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 2);
+    mock.write_all(b"out").await.unwrap();
+    mock.flush().await.unwrap();
+    mock.close().await.unwrap();
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 0);
+
+    let events: Vec<Event> = std::iter::from_fn(|| handle.pop_event()).collect();
+    assert_eq!(
+        events,
+        vec![
+            Event::Read,
+            Event::Read,
+            Event::Write(b"out".to_vec()),
+            Event::Flush,
+            Event::Shutdown,
+            Event::Eof
+        ]
+    );
+}
+
+#[tokio::test]
+async fn futures_errors() {
+    let (mut mock, mut handle) = Builder::new().build_with_handle();
+    handle.read_error(std::io::ErrorKind::ConnectionReset);
+    handle.write_error(std::io::ErrorKind::BrokenPipe);
+    let mut buf = [0; 8];
+    let err = mock.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::ConnectionReset);
+    let err = mock.write(b"x").await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+}
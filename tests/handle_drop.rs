@@ -0,0 +1,93 @@
+use std::io::{ErrorKind, SeekFrom};
+
+use sfio_tokio_mock_io::{mock, Builder, OnHandleDrop};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+#[tokio::test]
+#[should_panic(expected = "The sending side of the channel was closed")]
+async fn panic_when_queue_is_empty() {
+    let (mut mock, handle) = mock();
+    drop(handle);
+    let mut buf = [0; 4];
+    let _ = mock.read(&mut buf).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "The sending side of the channel was closed")]
+async fn panic_after_queued_actions() {
+    let (mut mock, mut handle) = mock();
+    handle.read(b"ab");
+    handle.write(b"cd");
+    drop(handle);
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+    mock.write_all(b"cd").await.unwrap();
+    let _ = mock.read(&mut buf).await;
+}
+
+#[tokio::test]
+async fn flush_and_shutdown_succeed_after_drop() {
+    let (mut mock, handle) = mock();
+    drop(handle);
+    mock.flush().await.unwrap();
+    mock.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn queued_flush_error_survives_drop() {
+    let (mut mock, mut handle) = mock();
+    handle.flush_error(ErrorKind::WriteZero);
+    drop(handle);
+    let err = mock.flush().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::WriteZero);
+    mock.flush().await.unwrap();
+}
+
+#[tokio::test]
+async fn disconnect_behaves_like_closed_peer() {
+    let (mut mock, mut handle) = mock();
+    mock.set_on_handle_drop(OnHandleDrop::Disconnect);
+    handle.read(b"discarded");
+    drop(handle);
+    let mut buf = [0; 4];
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 0);
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 0);
+    let err = mock.write(b"x").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    let err = mock.flush().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    let err = mock.shutdown().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    let err = mock.seek(SeekFrom::Start(0)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+}
+
+#[tokio::test]
+async fn disconnect_wakes_parked_read() {
+    let (mut mock, handle) = Builder::new()
+        .on_handle_drop(OnHandleDrop::Disconnect)
+        .build_with_handle();
+    let reader = tokio::spawn(async move {
+        let mut buf = [0; 4];
+        mock.read(&mut buf).await.unwrap()
+    });
+    tokio::task::yield_now().await;
+    drop(handle);
+    assert_eq!(reader.await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn continue_runs_remaining_script() {
+    let (mut mock, mut handle) = Builder::new()
+        .on_handle_drop(OnHandleDrop::Continue)
+        .build_with_handle();
+    handle.read(b"ab");
+    handle.write(b"cd");
+    handle.read_eof();
+    drop(handle);
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+    mock.write_all(b"cd").await.unwrap();
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 0);
+    mock.verify().unwrap();
+}
@@ -0,0 +1,191 @@
+use std::io::ErrorKind;
+
+use sfio_tokio_mock_io::{mock, Builder, Event, Handle, Mock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Copy, Clone, Debug)]
+enum Step {
+    Read(&'static [u8]),
+    Write(&'static [u8]),
+    Eof,
+    ReadError(ErrorKind),
+    WriteError(ErrorKind),
+}
+
+const SCRIPT: &[Step] = &[
+    Step::Read(b"hello"),
+    Step::Write(b"world"),
+    Step::Read(b"a"),
+    Step::Read(b"bc"),
+    Step::ReadError(ErrorKind::ConnectionReset),
+    Step::Write(b"x"),
+    Step::WriteError(ErrorKind::BrokenPipe),
+    Step::Write(b"yz"),
+    Step::Eof,
+    Step::Read(b"after eof"),
+];
+
+fn queue(handle: &mut Handle, step: Step) {
+    match step {
+        Step::Read(data) => handle.read(data),
+        Step::Write(data) => handle.write(data),
+        Step::Eof => handle.read_eof(),
+        Step::ReadError(kind) => handle.read_error(kind),
+        Step::WriteError(kind) => handle.write_error(kind),
+    }
+}
+
+fn script(builder: &mut Builder, step: Step) {
+    match step {
+        Step::Read(data) => builder.read(data),
+        Step::Write(data) => builder.write(data),
+        Step::Eof => builder.read_eof(),
+        Step::ReadError(kind) => builder.read_error(kind),
+        Step::WriteError(kind) => builder.write_error(kind),
+    };
+}
+
+/// Perform the operation that consumes `step`, describing its outcome
+async fn perform(mock: &mut Mock, step: Step) -> String {
+    match step {
+        Step::Read(data) => {
+            let mut buf = vec![0; data.len()];
+            match mock.read_exact(&mut buf).await {
+                Ok(_) => format!("read {:?}", buf),
+                Err(err) => format!("read error {:?}", err.kind()),
+            }
+        }
+        Step::Eof | Step::ReadError(_) => {
+            let mut buf = [0; 16];
+            match mock.read(&mut buf).await {
+                Ok(count) => format!("read {:?}", &buf[..count]),
+                Err(err) => format!("read error {:?}", err.kind()),
+            }
+        }
+        Step::Write(data) => match mock.write(data).await {
+            Ok(count) => format!("wrote {}", count),
+            Err(err) => format!("write error {:?}", err.kind()),
+        },
+        Step::WriteError(_) => match mock.write(b"rejected").await {
+            Ok(count) => format!("wrote {}", count),
+            Err(err) => format!("write error {:?}", err.kind()),
+        },
+    }
+}
+
+fn events(handle: &mut Handle) -> Vec<Event> {
+    std::iter::from_fn(|| handle.pop_event()).collect()
+}
+
+async fn run(mut mock: Mock, mut handle: Handle, burst: bool) -> (Vec<String>, Vec<Event>) {
+    if burst {
+        for step in SCRIPT {
+            queue(&mut handle, *step);
+        }
+    }
+    let mut outcomes = Vec::new();
+    for step in SCRIPT {
+        if !burst {
+            queue(&mut handle, *step);
+        }
+        outcomes.push(perform(&mut mock, *step).await);
+    }
+    (outcomes, events(&mut handle))
+}
+
+#[tokio::test]
+async fn burst_matches_one_by_one() {
+    let (mock_a, handle_a) = mock();
+    let (mock_b, handle_b) = mock();
+    let burst = run(mock_a, handle_a, true).await;
+    let one_by_one = run(mock_b, handle_b, false).await;
+    assert_eq!(burst, one_by_one);
+    assert_eq!(burst.1.len(), SCRIPT.len());
+}
+
+#[tokio::test]
+async fn builder_matches_one_by_one() {
+    let mut builder = Builder::new();
+    for step in SCRIPT {
+        script(&mut builder, *step);
+    }
+    let (mut scripted, mut handle) = builder.build_with_handle();
+    let mut outcomes = Vec::new();
+    for step in SCRIPT {
+        outcomes.push(perform(&mut scripted, *step).await);
+    }
+    let scripted = (outcomes, events(&mut handle));
+
+    let (mock, handle) = mock();
+    assert_eq!(scripted, run(mock, handle, false).await);
+}
+
+#[tokio::test]
+async fn burst_wakes_parked_reader() {
+    let (mut mock, mut handle) = mock();
+    let reader = tokio::spawn(async move {
+        let mut buf = [0; 6];
+        mock.read_exact(&mut buf).await.unwrap();
+        buf
+    });
+    tokio::task::yield_now().await;
+    assert!(!reader.is_finished());
+
+    handle.read(b"ab");
+    handle.read(b"cd");
+    handle.read(b"ef");
+    assert_eq!(&reader.await.unwrap(), b"abcdef");
+    assert_eq!(
+        events(&mut handle),
+        vec![Event::Read, Event::Read, Event::Read]
+    );
+}
+
+#[tokio::test]
+async fn burst_behind_write_wakes_reader() {
+    let (mock, mut handle) = mock();
+    let (mut reader, mut writer) = tokio::io::split(mock);
+    let reader = tokio::spawn(async move {
+        let mut buf = [0; 3];
+        reader.read_exact(&mut buf).await.unwrap();
+        buf
+    });
+    tokio::task::yield_now().await;
+
+    handle.write(b"ping");
+    handle.read(b"pon");
+    tokio::task::yield_now().await;
+    assert!(!reader.is_finished());
+
+    writer.write_all(b"ping").await.unwrap();
+    assert_eq!(&reader.await.unwrap(), b"pon");
+    assert_eq!(handle.next_event().await, Event::Write(b"ping".to_vec()));
+    assert_eq!(handle.next_event().await, Event::Read);
+}
+
+#[tokio::test]
+async fn burst_during_partial_read() {
+    let (mut mock, mut handle) = mock();
+    handle.read(b"abcd");
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+
+    handle.read(b"ef");
+    handle.read_error(ErrorKind::ConnectionAborted);
+    handle.read(b"g");
+    let mut buf = [0; 4];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"cdef");
+    let err = mock.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+    assert_eq!(mock.read(&mut buf).await.unwrap(), 1);
+    assert_eq!(
+        events(&mut handle),
+        vec![
+            Event::Read,
+            Event::Read,
+            Event::ReadErr(ErrorKind::ConnectionAborted),
+            Event::Read
+        ]
+    );
+}
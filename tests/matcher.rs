@@ -0,0 +1,114 @@
+use sfio_tokio_mock_io::{mock, Builder, Event, Handle, Matcher, Mock};
+use tokio::io::AsyncWriteExt;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn mock_is_send_and_sync() {
+    assert_send_sync::<Mock>();
+    assert_send_sync::<Handle>();
+    assert_send_sync::<Builder>();
+    assert_send_sync::<Matcher>();
+}
+
+#[tokio::test]
+async fn each_matcher_accepts_a_matching_write() {
+    let (mut mock, mut handle) = Builder::new()
+        .write_matching(Matcher::Exact(vec![5, 6]))
+        .write_matching(Matcher::Mask(vec![Some(1), None, Some(3)]))
+        .write_matching(Matcher::Length(2))
+        .write_matching(Matcher::Prefix(vec![9]))
+        .write_matching(Matcher::Suffix(vec![8]))
+        .write_matching(Matcher::custom(|x| x.len() == 1))
+        .build_with_handle();
+    mock.write_all(&[5, 6]).await.unwrap();
+    mock.write_all(&[1, 77, 3]).await.unwrap();
+    mock.write_all(&[0, 0]).await.unwrap();
+    mock.write_all(&[9, 1, 2]).await.unwrap();
+    mock.write_all(&[4, 8]).await.unwrap();
+    mock.write_all(&[4]).await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(vec![5, 6])));
+    assert_eq!(handle.pop_event(), Some(Event::Write(vec![1, 77, 3])));
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "Unexpected write at byte 2\nexpected (3 bytes): [01 ?? 03]\n  actual (3 bytes): [01 02 04]"
+)]
+async fn mask_mismatch() {
+    let mut mock = Builder::new()
+        .write_matching(Matcher::Mask(vec![Some(1), None, Some(3)]))
+        .build();
+    mock.write_all(&[1, 2, 4]).await.unwrap();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 2\nexpected (2 bytes): any")]
+async fn length_mismatch() {
+    let (mut mock, mut handle) = mock();
+    handle.write_matching(Matcher::Length(2));
+    let _ = mock.write(&[1, 2, 3]).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 1\nexpected prefix (2 bytes): [09 09]")]
+async fn prefix_mismatch() {
+    let (mut mock, mut handle) = mock();
+    handle.write_matching(Matcher::Prefix(vec![9, 9]));
+    let _ = mock.write(&[9, 1, 2]).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected write at byte 0\nexpected suffix (2 bytes): [08 08]")]
+async fn suffix_longer_than_write() {
+    let (mut mock, mut handle) = mock();
+    handle.write_matching(Matcher::Suffix(vec![8, 8]));
+    let _ = mock.write(&[8]).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "expected custom predicate")]
+async fn custom_mismatch() {
+    let (mut mock, mut handle) = mock();
+    handle.write_matching(Matcher::custom(|x| x.starts_with(b"GET ")));
+    let _ = mock.write(b"POST /").await;
+}
+
+#[tokio::test]
+async fn matchers_span_short_writes() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write_matching(Matcher::Exact(b"hello".to_vec()));
+    handle.write_limit(1);
+    handle.write_matching(Matcher::custom(|x| x == b"abc"));
+    mock.write_all(b"hello").await.unwrap();
+    mock.write_all(b"abc").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"he".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"llo".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"a".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"bc".to_vec())));
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn short_write_satisfies_prefix() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write_matching(Matcher::Prefix(b"he".to_vec()));
+    handle.write(b"llo");
+    mock.write_all(b"hello").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"he".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"llo".to_vec())));
+    handle.verify().unwrap();
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "Unexpected write at byte 1\nexpected (5 bytes): [68 65 6C 6C 6F]\n  actual (2 bytes): [68 6F]"
+)]
+async fn short_write_mismatch() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write_matching(Matcher::Exact(b"hello".to_vec()));
+    let _ = mock.write(b"hoppy").await;
+}
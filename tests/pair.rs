@@ -0,0 +1,116 @@
+use std::io::{Error, ErrorKind};
+
+use sfio_tokio_mock_io::{pair, Event, Side};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn bytes_flow_between_endpoints() {
+    let (mut a, mut b, mut tap) = pair();
+    a.write_all(b"hello").await.unwrap();
+    let mut buf = [0; 5];
+    b.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+    b.write_all(b"hi").await.unwrap();
+    let mut buf = [0; 2];
+    a.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hi");
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::A, Event::Write(b"hello".to_vec())))
+    );
+    assert_eq!(tap.pop_event(), Some((Side::B, Event::Read)));
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::B, Event::Write(b"hi".to_vec())))
+    );
+    assert_eq!(tap.pop_event(), Some((Side::A, Event::Read)));
+}
+
+#[tokio::test]
+async fn tap_drops_and_holds_bytes() {
+    let (mut a, mut b, mut tap) = pair();
+    tap.drop_bytes(Side::B, 2);
+    tap.hold(Side::B);
+    b.write_all(b"xyabc").await.unwrap();
+    let reader = tokio::spawn(async move {
+        let mut data = Vec::new();
+        a.read_to_end(&mut data).await.unwrap();
+        data
+    });
+    b.shutdown().await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(!reader.is_finished());
+    tap.release(Side::B);
+    assert_eq!(reader.await.unwrap(), b"abc");
+}
+
+#[tokio::test]
+async fn tap_injects_errors() {
+    let (mut a, mut b, mut tap) = pair();
+    tap.read_error(Side::B, ErrorKind::ConnectionReset);
+    tap.write_error_with(Side::A, Error::other("injected"));
+    tap.read_error_with(Side::A, Error::from_raw_os_error(104));
+    let mut buf = [0; 1];
+    let err = b.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+    let err = a.write(b"x").await.unwrap_err();
+    assert_eq!(err.to_string(), "injected");
+    let err = a.read(&mut buf).await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(104));
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::B, Event::ReadErr(ErrorKind::ConnectionReset)))
+    );
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::A, Event::WriteErr(ErrorKind::Other)))
+    );
+}
+
+#[tokio::test]
+async fn half_close_lets_peer_reply() {
+    let (mut client, mut server, mut tap) = pair();
+    client.write_all(b"request").await.unwrap();
+    client.shutdown().await.unwrap();
+
+    let mut request = Vec::new();
+    server.read_to_end(&mut request).await.unwrap();
+    assert_eq!(request, b"request");
+    server.write_all(b"reply").await.unwrap();
+    server.shutdown().await.unwrap();
+
+    let mut reply = Vec::new();
+    client.read_to_end(&mut reply).await.unwrap();
+    assert_eq!(reply, b"reply");
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::A, Event::Write(b"request".to_vec())))
+    );
+    assert_eq!(tap.pop_event(), Some((Side::A, Event::Shutdown)));
+}
+
+#[tokio::test]
+async fn write_after_own_shutdown_fails() {
+    let (mut a, mut b, mut tap) = pair();
+    a.shutdown().await.unwrap();
+    let err = a.write(b"late").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    let mut data = Vec::new();
+    b.read_to_end(&mut data).await.unwrap();
+    assert!(data.is_empty());
+    assert_eq!(tap.pop_event(), Some((Side::A, Event::Shutdown)));
+    assert_eq!(
+        tap.pop_event(),
+        Some((Side::A, Event::WriteErr(ErrorKind::BrokenPipe)))
+    );
+}
+
+#[tokio::test]
+async fn write_after_peer_drop_fails() {
+    let (mut a, b, _tap) = pair();
+    drop(b);
+    let err = a.write(b"x").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    let mut buf = [0; 1];
+    assert_eq!(a.read(&mut buf).await.unwrap(), 0);
+}
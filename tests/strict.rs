@@ -0,0 +1,27 @@
+use sfio_tokio_mock_io::{mock, Event};
+use tokio::io::AsyncWriteExt;
+
+#[tokio::test]
+#[should_panic(expected = "1 unread mock event(s):\n  1: flush")]
+async fn strict_handle_panics_on_unread_events() {
+    let (mut mock, mut handle) = mock();
+    handle.set_strict(true);
+    mock.flush().await.unwrap();
+    drop(handle);
+}
+
+#[tokio::test]
+async fn strict_handle_is_silent_once_events_are_read() {
+    let (mut mock, mut handle) = mock();
+    handle.set_strict(true);
+    mock.flush().await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Flush));
+    drop(handle);
+}
+
+#[tokio::test]
+async fn non_strict_handle_is_silent() {
+    let (mut mock, handle) = mock();
+    mock.flush().await.unwrap();
+    drop(handle);
+}
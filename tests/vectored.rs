@@ -0,0 +1,45 @@
+use std::io::IoSlice;
+
+use sfio_tokio_mock_io::{mock, Builder, Event};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+#[tokio::test]
+async fn vectored_write_reports_each_slice() {
+    let (mut mock, mut handle) = Builder::new()
+        .write_vectored(true)
+        .write(b"abcde")
+        .build_with_handle();
+    assert!(mock.is_write_vectored());
+    let slices = [IoSlice::new(b"ab"), IoSlice::new(b""), IoSlice::new(b"cde")];
+    assert_eq!(mock.write_vectored(&slices).await.unwrap(), 5);
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::WriteVectored(vec![
+            b"ab".to_vec(),
+            vec![],
+            b"cde".to_vec()
+        ]))
+    );
+    handle.assert_idle();
+}
+
+#[tokio::test]
+async fn short_vectored_write_is_split_by_slice() {
+    let (mut mock, mut handle) = Builder::new().write_vectored(true).build_with_handle();
+    handle.write_limit(3);
+    let slices = [IoSlice::new(b"ab"), IoSlice::new(b"cde")];
+    assert_eq!(mock.write_vectored(&slices).await.unwrap(), 3);
+    assert_eq!(
+        handle.pop_event(),
+        Some(Event::WriteVectored(vec![b"ab".to_vec(), b"c".to_vec()]))
+    );
+}
+
+#[tokio::test]
+async fn default_writes_first_non_empty_slice() {
+    let (mut mock, mut handle) = mock();
+    assert!(!mock.is_write_vectored());
+    let slices = [IoSlice::new(b""), IoSlice::new(b"cde"), IoSlice::new(b"f")];
+    assert_eq!(mock.write_vectored(&slices).await.unwrap(), 3);
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"cde".to_vec())));
+}
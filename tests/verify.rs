@@ -0,0 +1,81 @@
+use sfio_tokio_mock_io::{mock, Builder, Event};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn verify_reports_rest_of_partial_read() {
+    let (mut mock, mut handle) = mock();
+    handle.read(b"abcdef");
+    let mut buf = [0; 2];
+    mock.read_exact(&mut buf).await.unwrap();
+
+    let err = handle.verify().unwrap_err();
+    assert_eq!(
+        err.actions,
+        vec!["read (4 bytes): [63 64 65 66]".to_string()]
+    );
+    assert!(err.events.is_empty());
+    let err = mock.verify().unwrap_err();
+    assert_eq!(
+        err.actions,
+        vec!["read (4 bytes): [63 64 65 66]".to_string()]
+    );
+    assert!(err.events.is_empty());
+
+    let mut buf = [0; 4];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    handle.verify().unwrap();
+}
+
+#[tokio::test]
+async fn verify_reports_rest_of_short_write() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.write(b"hello");
+    assert_eq!(mock.write(b"hello").await.unwrap(), 2);
+
+    let err = handle.verify().unwrap_err();
+    assert_eq!(err.actions, vec!["write (3 bytes): [6C 6C 6F]".to_string()]);
+    assert_eq!(err.events, vec![Event::Write(b"he".to_vec())]);
+    let err = mock.verify().unwrap_err();
+    assert_eq!(err.actions, vec!["write (3 bytes): [6C 6C 6F]".to_string()]);
+
+    mock.write_all(b"llo").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"llo".to_vec())));
+    handle.verify().unwrap();
+}
+
+#[tokio::test]
+async fn verify_reports_rest_of_coalesced_write() {
+    let (mut mock, mut handle) = Builder::new()
+        .coalesce_writes(true)
+        .write(b"hello")
+        .read(b"x")
+        .build_with_handle();
+    mock.write_all(b"hel").await.unwrap();
+
+    let err = handle.verify().unwrap_err();
+    assert_eq!(
+        err.actions,
+        vec![
+            "write (2 bytes): [6C 6F]".to_string(),
+            "read (1 bytes): [78]".to_string()
+        ]
+    );
+    assert_eq!(err.events, vec![Event::Write(b"hel".to_vec())]);
+    let err = mock.verify().unwrap_err();
+    assert_eq!(
+        err.actions,
+        vec![
+            "write (2 bytes): [6C 6F]".to_string(),
+            "read (1 bytes): [78]".to_string()
+        ]
+    );
+
+    mock.write_all(b"lo").await.unwrap();
+    let mut buf = [0; 1];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"lo".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+    handle.verify().unwrap();
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "time")]
+
+use std::time::Duration;
+
+use sfio_tokio_mock_io::{mock, Builder, Event};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::Instant;
+
+#[tokio::test(start_paused = true)]
+async fn read_arrives_after_wait() {
+    let (mut mock, mut handle) = mock();
+    handle.wait(Duration::from_secs(5));
+    handle.read(b"late");
+    let start = Instant::now();
+    let mut buf = [0; 4];
+    mock.read_exact(&mut buf).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(5));
+    assert_eq!(&buf, b"late");
+    assert_eq!(handle.pop_event(), Some(Event::Read));
+}
+
+#[tokio::test(start_paused = true)]
+async fn read_times_out_during_wait() {
+    let mut mock = Builder::new()
+        .wait(Duration::from_secs(10))
+        .read(b"late")
+        .build();
+    let mut buf = [0; 4];
+    let result = tokio::time::timeout(Duration::from_secs(3), mock.read(&mut buf)).await;
+    assert!(result.is_err());
+    mock.read_exact(&mut buf).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn consecutive_waits_add_up() {
+    let mut mock = Builder::new()
+        .wait(Duration::from_secs(1))
+        .wait(Duration::from_secs(2))
+        .write(b"ping")
+        .build();
+    let start = Instant::now();
+    mock.write_all(b"ping").await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_wakes_every_parked_operation() {
+    let (mock, mut handle) = mock();
+    handle.wait(Duration::from_secs(5));
+    handle.write(b"ping");
+    handle.read(b"pong");
+    let (mut reader, mut writer) = tokio::io::split(mock);
+
+    let writer = tokio::spawn(async move { writer.write_all(b"ping").await.unwrap() });
+    tokio::task::yield_now().await;
+    let reader = tokio::spawn(async move {
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).await.unwrap();
+        buf
+    });
+
+    tokio::time::timeout(Duration::from_secs(60), writer)
+        .await
+        .expect("writer was not woken after the wait")
+        .unwrap();
+    assert_eq!(&reader.await.unwrap(), b"pong");
+}
+
+#[tokio::test(start_paused = true)]
+async fn write_limit_survives_following_wait() {
+    let (mut mock, mut handle) = mock();
+    handle.write_limit(2);
+    handle.wait(Duration::from_secs(1));
+    handle.write(b"hello");
+    let start = Instant::now();
+    assert_eq!(mock.write(b"hello").await.unwrap(), 2);
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+    mock.write_all(b"llo").await.unwrap();
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"he".to_vec())));
+    assert_eq!(handle.pop_event(), Some(Event::Write(b"llo".to_vec())));
+    handle.assert_idle();
+}